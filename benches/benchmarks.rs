@@ -1,9 +1,10 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use spider_network_blocker::intercept_manager::NetworkInterceptManager;
 use spider_network_blocker::scripts::{
     URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_SCRIPT_BASE_PATHS, URL_IGNORE_TRIE,
 };
 use spider_network_blocker::xhr::{URL_IGNORE_XHR_MEDIA_TRIE, URL_IGNORE_XHR_TRIE};
+use std::hint::black_box;
 
 fn bench_trie_prefix_matching(c: &mut Criterion) {
     let mut group = c.benchmark_group("trie_prefix_matching");
@@ -194,6 +195,21 @@ fn bench_intercept_detection(c: &mut Criterion) {
         })
     });
 
+    group.bench_function("manager_from_host", |b| {
+        b.iter(|| {
+            black_box(NetworkInterceptManager::from_host(black_box(
+                "www.amazon.com",
+            )));
+        })
+    });
+
+    let parsed = url::Url::parse("https://www.amazon.com/dp/B08N5WRWNW").unwrap();
+    group.bench_function("manager_from_url", |b| {
+        b.iter(|| {
+            black_box(NetworkInterceptManager::from_url(black_box(&parsed)));
+        })
+    });
+
     group.finish();
 }
 
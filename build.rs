@@ -1,12 +1,28 @@
 extern crate phf_codegen;
-use convert_case::{Case, Casing};
 use std::env;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+#[path = "build/domains.rs"]
+mod domains;
+// Shared with the library and the tests; not every item is used here.
+#[allow(dead_code)]
+#[path = "src/pattern_file.rs"]
+mod pattern_file;
+#[allow(dead_code)]
+#[path = "build/static_trie.rs"]
+mod static_trie;
+
+use domains::DomainDir;
+use pattern_file::{MatchMode, PatternEntry};
+use static_trie::PackedTrie;
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=build");
+    println!("cargo:rerun-if-changed=src/pattern_file.rs");
+    println!("cargo:rerun-if-changed=url_patterns");
 
     let out_dir = env::var("OUT_DIR").unwrap();
     let domain_map_path = Path::new(&out_dir).join("domain_map.rs");
@@ -15,18 +31,32 @@ fn main() {
     fs::create_dir_all(&blockers_dir).unwrap();
 
     let pattern_dir = "url_patterns/domains";
-
-    generate_domain_map(&domain_map_path, pattern_dir);
-    generate_url_ignore_tries(&url_trie_path, pattern_dir);
-    generate_blockers(&blockers_dir, pattern_dir);
-    generate_blockers_mod(&blockers_dir, pattern_dir);
+    let domains = domains::scan(Path::new(pattern_dir)).unwrap_or_else(|e| panic!("{}", e));
+
+    generate_public_suffixes(
+        &Path::new(&out_dir).join("public_suffix.rs"),
+        "url_patterns/public_suffix_list.dat",
+    );
+
+    for module in ["scripts", "xhr"] {
+        generate_global_tries(
+            &Path::new(&out_dir).join(format!("{}_tries.rs", module)),
+            "url_patterns/global",
+            module,
+        );
+    }
+    let lists: Vec<Vec<ModeLists>> = domains.iter().map(domain_lists).collect();
+    generate_domain_map(&domain_map_path, &domains);
+    generate_url_ignore_tries(&url_trie_path, &domains, &lists);
+    generate_blockers(&blockers_dir, &domains, &lists);
+    generate_blockers_mod(&blockers_dir, &domains);
 
     #[cfg(feature = "adblock_easylist")]
     easylist::fetch_lists(&out_dir);
 }
 
-fn generate_domain_map(domain_map_path: &Path, pattern_dir: &str) {
-    let mut file = BufWriter::new(File::create(&domain_map_path).unwrap());
+fn generate_domain_map(domain_map_path: &Path, domains: &[DomainDir]) {
+    let mut file = BufWriter::new(File::create(domain_map_path).unwrap());
     let mut map = phf_codegen::Map::new();
 
     writeln!(file, "mod blockers;\nmod url_ignore_trie;").unwrap();
@@ -42,22 +72,16 @@ fn generate_domain_map(domain_map_path: &Path, pattern_dir: &str) {
     .unwrap();
     writeln!(&mut file, "pub enum NetworkInterceptManager {{").unwrap();
 
-    let mut domain_variants = vec![];
     let mut map_entries: Vec<(String, String)> = vec![];
 
-    for entry in fs::read_dir(pattern_dir).unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-
-        if let Some(domain_name) = path.file_stem().unwrap().to_str() {
-            let enum_name = format_ident(domain_name).to_case(Case::UpperCamel);
-            writeln!(&mut file, "    {},", enum_name).unwrap();
-            domain_variants.push((domain_name.to_string(), enum_name.clone()));
-            map_entries.push((
-                domain_name.to_string(),
-                format!("NetworkInterceptManager::{}", enum_name),
-            ));
-        }
+    for domain in domains {
+        writeln!(&mut file, "    {},", domain.variant).unwrap();
+        // Keyed on the full directory name (`amazon.com`,
+        // `web.archive.org`) so domains sharing a label never collide.
+        map_entries.push((
+            domain.name.clone(),
+            format!("NetworkInterceptManager::{}", domain.variant),
+        ));
     }
 
     for (key, value) in &map_entries {
@@ -67,197 +91,485 @@ fn generate_domain_map(domain_map_path: &Path, pattern_dir: &str) {
     writeln!(&mut file, "    #[default]\n    Unknown,").unwrap(); // Default case
     writeln!(&mut file, "}}\n").unwrap();
 
-    write!(
+    writeln!(
         file,
-        "static DOMAIN_MAP: phf::Map<&'static str, NetworkInterceptManager> = {};\n",
+        "static DOMAIN_MAP: phf::Map<&'static str, NetworkInterceptManager> = {};",
         map.build()
     )
     .unwrap();
 
     writeln!(file, "impl NetworkInterceptManager {{").unwrap();
-    writeln!(file, "    pub fn intercept_detection(&self, url: &str, ignore_visuals: bool, is_xhr: bool) -> bool {{").unwrap();
-    writeln!(file, "        let mut should_block = false;").unwrap();
+    writeln!(
+        file,
+        "    /// Check `url` against this domain's `allow` list, which overrides its\n    /// own block lists and the global tries."
+    )
+    .unwrap();
+    writeln!(file, "    pub fn is_allowed(&self, url: &str) -> bool {{").unwrap();
     writeln!(file, "        match self {{").unwrap();
-
-    for (domain_name, enum_name) in domain_variants {
-        let clean_name = domain_name.split('.').next().unwrap().to_lowercase();
-        writeln!(
-            file,
-            "            NetworkInterceptManager::{} => {{",
-            enum_name
-        )
-        .unwrap();
-        writeln!(file, "                if is_xhr {{").unwrap();
+    for domain in domains {
         writeln!(
             file,
-            "                    should_block = blockers::{}_blockers::block_xhr(url);",
-            clean_name
-        )
-        .unwrap();
-        writeln!(file, "                }} else {{").unwrap();
-        writeln!(
-            file,
-            "                    should_block = blockers::{}_blockers::block_scripts(url);",
-            clean_name
-        )
-        .unwrap();
-        writeln!(
-            file,
-            "                    if !should_block && ignore_visuals {{"
+            "            NetworkInterceptManager::{} => blockers::{}::allow(url),",
+            domain.variant,
+            domain.module()
         )
         .unwrap();
+    }
+    writeln!(
+        file,
+        "            NetworkInterceptManager::Unknown => false,"
+    )
+    .unwrap();
+    writeln!(file, "        }}").unwrap();
+    writeln!(file, "    }}\n").unwrap();
+
+    writeln!(
+        file,
+        "    /// Check `url` against this domain's pattern set for `resource_type`,\n    /// or its script list if it has none for that type. XHR and fetch\n    /// requests only face the XHR list."
+    )
+    .unwrap();
+    writeln!(file, "    pub fn intercept_resource(&self, url: &str, resource_type: crate::resource_type::ResourceType) -> bool {{").unwrap();
+    writeln!(file, "        match self {{").unwrap();
+    for domain in domains {
         writeln!(
             file,
-            "                        should_block = blockers::{}_blockers::block_styles(url);",
-            clean_name
+            "            NetworkInterceptManager::{} => blockers::{}::block_resource(url, resource_type),",
+            domain.variant,
+            domain.module()
         )
         .unwrap();
-        writeln!(file, "                    }}").unwrap();
-        writeln!(file, "                }}").unwrap();
-        writeln!(file, "            }},").unwrap();
     }
-
-    writeln!(file, "            NetworkInterceptManager::Unknown => (),").unwrap();
-
+    writeln!(
+        file,
+        "            NetworkInterceptManager::Unknown => false,"
+    )
+    .unwrap();
     writeln!(file, "        }}").unwrap();
-    writeln!(file, "        should_block").unwrap();
+    writeln!(file, "    }}\n").unwrap();
+
+    writeln!(
+        file,
+        "    /// Check `url` the way [`intercept_resource`](Self::intercept_resource)\n    /// does for an XHR request, or for a script (plus styles, images, fonts\n    /// and media with `ignore_visuals`) otherwise."
+    )
+    .unwrap();
+    writeln!(file, "    pub fn intercept_detection(&self, url: &str, ignore_visuals: bool, is_xhr: bool) -> bool {{").unwrap();
+    writeln!(
+        file,
+        "        crate::resource_type::ResourceType::detection_types(ignore_visuals, is_xhr)"
+    )
+    .unwrap();
+    writeln!(file, "            .iter()").unwrap();
+    writeln!(
+        file,
+        "            .any(|&resource_type| self.intercept_resource(url, resource_type))"
+    )
+    .unwrap();
     writeln!(file, "    }}").unwrap();
     writeln!(file, "}}").unwrap();
 }
 
-fn generate_url_ignore_tries(url_trie_path: &Path, pattern_dir: &str) {
-    let mut file = BufWriter::new(File::create(url_trie_path).unwrap());
-
-    writeln!(file, "use crate::trie::Trie;").unwrap();
-    writeln!(file, "use std::sync::LazyLock;").unwrap();
-
-    for category in &["scripts", "xhr", "styles"] {
-        if let Ok(domain_entries) = fs::read_dir(pattern_dir) {
-            for domain_entry in domain_entries {
-                let domain_entry = domain_entry.unwrap();
-                let domain_path = domain_entry.path();
-
-                if domain_path.is_dir() {
-                    let domain_name = domain_path.file_name().unwrap().to_str().unwrap();
-                    let category_domain_path = domain_path.join(category);
-
-                    if let Ok(category_entries) = fs::read_dir(&category_domain_path) {
-                        let trie_name = format_ident(&format!("{}_{}", domain_name, category));
-                        writeln!(
-                            file,
-                            "pub static {}_TRIE: LazyLock<Trie> = LazyLock::new(|| {{",
-                            trie_name.to_uppercase()
-                        )
-                        .unwrap();
-
-                        let mut has_ignore = false;
-
-                        for entry in category_entries {
-                            let entry = entry.unwrap();
-                            let path = entry.path();
-
-                            if path.is_file() {
-                                let contents = fs::read_to_string(path).unwrap();
-
-                                if !has_ignore && !contents.is_empty() {
-                                    writeln!(file, "let mut trie = Trie::new();").unwrap();
-                                    has_ignore = true;
-                                }
-
-                                for pattern in contents.lines() {
-                                    writeln!(file, "trie.insert({:?});", pattern.trim()).unwrap();
-                                }
-                            }
-                        }
+/// Compile the Public Suffix List into three phf sets: plain rules, the
+/// parents of `*.` wildcard rules, and `!` exception rules. Non-ASCII rules
+/// are also stored in their punycode form, which is what `url` hands out.
+fn generate_public_suffixes(out_path: &Path, list_path: &str) {
+    use std::collections::BTreeSet;
+
+    let list = fs::read_to_string(list_path).unwrap();
+    let mut rules = BTreeSet::new();
+    let mut wildcards = BTreeSet::new();
+    let mut exceptions = BTreeSet::new();
+
+    for line in list.lines() {
+        // Rules end at the first whitespace; `//` starts a comment line.
+        let rule = line.split_whitespace().next().unwrap_or_default();
+        if rule.is_empty() || rule.starts_with("//") {
+            continue;
+        }
+        let (set, rule) = if let Some(parent) = rule.strip_prefix("*.") {
+            (&mut wildcards, parent)
+        } else if let Some(exception) = rule.strip_prefix('!') {
+            (&mut exceptions, exception)
+        } else {
+            (&mut rules, rule)
+        };
+        let rule = rule.to_lowercase();
+        if !rule.is_ascii() {
+            match idna::domain_to_ascii(&rule) {
+                Ok(ascii) => {
+                    set.insert(ascii);
+                }
+                Err(_) => panic!("{}: cannot punycode rule {:?}", list_path, rule),
+            }
+        }
+        set.insert(rule);
+    }
 
-                        if !has_ignore {
-                            writeln!(file, "let trie = Trie::new();").unwrap();
-                        }
+    let mut file = BufWriter::new(File::create(out_path).unwrap());
+    // The list is MPL-2.0; keep its license header on the derived tables.
+    for line in list.lines().take_while(|l| l.starts_with("//")) {
+        writeln!(file, "{}", line).unwrap();
+    }
+    writeln!(file).unwrap();
+    for (name, set) in [
+        ("RULES", &rules),
+        ("WILDCARDS", &wildcards),
+        ("EXCEPTIONS", &exceptions),
+    ] {
+        let mut codegen = phf_codegen::Set::new();
+        for rule in set {
+            codegen.entry(rule.as_str());
+        }
+        writeln!(
+            file,
+            "static {}: phf::Set<&'static str> = {};",
+            name,
+            codegen.build()
+        )
+        .unwrap();
+    }
+}
 
-                        writeln!(file, "trie").unwrap();
-                        writeln!(file, "}});").unwrap();
+/// Pack each of `module`'s [`GLOBAL_LISTS`] into a `StaticTrie` static.
+fn generate_global_tries(out_path: &Path, global_dir: &str, module: &str) {
+    let mut file = BufWriter::new(File::create(out_path).unwrap());
+    for (category, _, name, doc) in GLOBAL_LISTS.iter().filter(|l| l.1 == module) {
+        let dir = Path::new(global_dir).join(category);
+        if !dir.is_dir() {
+            panic!("{}: missing pattern directory for {}", dir.display(), name);
+        }
+        let mut patterns = Vec::new();
+        for (path, expires, entry) in read_patterns(&dir) {
+            if entry.mode != MatchMode::Prefix || entry.allow {
+                panic!(
+                    "{}:{}: global lists only take URL prefixes, not `{}`",
+                    path.display(),
+                    entry.line,
+                    if entry.allow {
+                        "!allow"
+                    } else {
+                        entry.mode.directive()
                     }
-                }
+                );
             }
+            patterns.push((entry.pattern, expires));
         }
+        writeln!(file, "/// {}", doc).unwrap();
+        writeln!(
+            file,
+            "pub static {}: crate::trie::StaticTrie<'static> = {};",
+            name,
+            packed(&patterns).to_rust()
+        )
+        .unwrap();
     }
 }
 
-fn generate_blockers(blockers_dir: &Path, pattern_dir: &str) {
-    if let Ok(domain_entries) = fs::read_dir(pattern_dir) {
-        for domain_entry in domain_entries {
-            let domain_entry = domain_entry.unwrap();
-            let domain_path = domain_entry.path();
-
-            if domain_path.is_dir() {
-                let domain_name = domain_path.file_name().unwrap().to_str().unwrap();
-                let file_name = format!("{}_blockers.rs", domain_name.split('.').next().unwrap());
-                let file_path = blockers_dir.join(file_name);
-                let mut file = BufWriter::new(File::create(file_path).unwrap());
-
-                // Generate block_scripts
-                let scripts_trie_name = format_ident(&format!("{}_scripts", domain_name));
-                writeln!(file, "pub fn block_scripts(url: &str) -> bool {{").unwrap();
+/// Emit the statics behind each domain list: a `StaticTrie` of URL
+/// prefixes, a `StaticHostTrie` of `@host` patterns and a lazily built
+/// `SubstringMatcher` of `@substring` patterns, each only when non-empty.
+fn generate_url_ignore_tries(
+    url_trie_path: &Path,
+    domains: &[DomainDir],
+    lists: &[Vec<ModeLists>],
+) {
+    let mut file = BufWriter::new(File::create(url_trie_path).unwrap());
+
+    for (i, category) in CATEGORIES.iter().chain([&ALLOW_CATEGORY]).enumerate() {
+        for (domain, lists) in domains.iter().zip(lists) {
+            let list = &lists[i];
+            if !list.prefixes.is_empty() {
                 writeln!(
                     file,
-                    "    crate::intercept_manager::url_ignore_trie::{}_TRIE.contains_prefix(url)",
-                    scripts_trie_name.to_uppercase()
+                    "pub static {}: crate::trie::StaticTrie<'static> = {};",
+                    domain.trie(category),
+                    packed(&list.prefixes).to_rust()
                 )
                 .unwrap();
-                writeln!(file, "}}\n").unwrap();
-
-                // Generate block_styles
-                let styles_trie_name = format_ident(&format!("{}_styles", domain_name));
-                writeln!(file, "pub fn block_styles(url: &str) -> bool {{").unwrap();
+            }
+            if !list.hosts.is_empty() {
+                let keys: Vec<(String, Option<u64>)> = list
+                    .hosts
+                    .iter()
+                    .map(|(h, expires)| (pattern_file::host_key(h), *expires))
+                    .collect();
                 writeln!(
                     file,
-                    "    crate::intercept_manager::url_ignore_trie::{}_TRIE.contains_prefix(url)",
-                    styles_trie_name.to_uppercase()
+                    "pub static {}: crate::host_trie::StaticHostTrie<'static> = crate::host_trie::StaticHostTrie::new({});",
+                    domain.hosts(category),
+                    packed(&keys).to_rust()
                 )
                 .unwrap();
-                writeln!(file, "}}\n").unwrap();
-
-                // Generate block_xhr
-                let xhr_trie_name = format_ident(&format!("{}_xhr", domain_name));
-                writeln!(file, "pub fn block_xhr(url: &str) -> bool {{").unwrap();
+            }
+            if !list.substrings.is_empty() {
                 writeln!(
                     file,
-                    "    crate::intercept_manager::url_ignore_trie::{}_TRIE.contains_prefix(url)",
-                    xhr_trie_name.to_uppercase()
+                    "pub static {}: std::sync::LazyLock<crate::substring::SubstringMatcher> = std::sync::LazyLock::new(|| crate::substring::SubstringMatcher::from_static_trie(&{}));",
+                    domain.substrings(category),
+                    packed(&list.substrings).to_rust()
                 )
                 .unwrap();
-                writeln!(file, "}}\n").unwrap();
             }
         }
     }
 }
 
-fn generate_blockers_mod(blockers_dir: &Path, pattern_dir: &str) {
-    let mod_file_path = blockers_dir.join("mod.rs");
-    let mut mod_file = BufWriter::new(File::create(mod_file_path).unwrap());
+/// Pack patterns paired with the Unix time they expire at, if any.
+fn packed(patterns: &[(String, Option<u64>)]) -> PackedTrie {
+    PackedTrie::with_expiry(patterns.iter().map(|(p, expires)| (p.as_str(), *expires)))
+}
+
+/// One list's patterns, split by match mode, each with its expiry cutoff.
+#[derive(Default)]
+struct ModeLists {
+    prefixes: Vec<(String, Option<u64>)>,
+    hosts: Vec<(String, Option<u64>)>,
+    substrings: Vec<(String, Option<u64>)>,
+}
 
-    if let Ok(domain_entries) = fs::read_dir(pattern_dir) {
-        for domain_entry in domain_entries {
-            let domain_entry = domain_entry.unwrap();
-            let clean_name = domain_entry
-                .file_name()
-                .to_str()
-                .unwrap_or_default()
-                .split('.')
-                .next()
-                .unwrap()
-                .to_lowercase();
-
-            writeln!(mod_file, "pub mod {}_blockers;", clean_name).unwrap();
+impl ModeLists {
+    fn push(&mut self, entry: PatternEntry, expires: Option<u64>) {
+        let list = match entry.mode {
+            MatchMode::Prefix => &mut self.prefixes,
+            MatchMode::Host => &mut self.hosts,
+            MatchMode::Substring => &mut self.substrings,
+        };
+        list.push((entry.pattern, expires));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.hosts.is_empty() && self.substrings.is_empty()
+    }
+
+    /// Boolean expressions checking `url` against each non-empty part.
+    fn checks(&self, domain: &DomainDir, category: &str) -> Vec<String> {
+        let path = "crate::intercept_manager::url_ignore_trie";
+        let mut checks = Vec::new();
+        if !self.prefixes.is_empty() {
+            checks.push(format!(
+                "{}::{}.contains_prefix(url)",
+                path,
+                domain.trie(category)
+            ));
+        }
+        if !self.hosts.is_empty() {
+            checks.push(format!(
+                "{}::{}.contains_url(url)",
+                path,
+                domain.hosts(category)
+            ));
         }
+        if !self.substrings.is_empty() {
+            checks.push(format!(
+                "{}::{}.is_match(url)",
+                path,
+                domain.substrings(category)
+            ));
+        }
+        checks
+    }
+}
+
+/// A domain's lists, one per [`CATEGORIES`] entry followed by the allow
+/// list. `!allow` lines in any category go to the allow list.
+fn domain_lists(domain: &DomainDir) -> Vec<ModeLists> {
+    let allow = CATEGORIES.len();
+    let mut lists: Vec<ModeLists> = (0..=allow).map(|_| ModeLists::default()).collect();
+    for (i, category) in CATEGORIES.iter().chain([&ALLOW_CATEGORY]).enumerate() {
+        let dir = domain.path.join(category);
+        if !dir.is_dir() {
+            continue;
+        }
+        for (_, expires, entry) in read_patterns(&dir) {
+            lists[if entry.allow { allow } else { i }].push(entry, expires);
+        }
+    }
+    lists
+}
+
+/// Every pattern in the files directly under `dir`, in file-name order,
+/// with the file it came from and the Unix time its `expires:` date ends.
+/// Panics with `file:line` on a malformed line. Expired files are kept:
+/// the generated tables check the cutoff at lookup, so the output doesn't
+/// depend on when the build ran.
+fn read_patterns(dir: &Path) -> Vec<(PathBuf, Option<u64>, PatternEntry)> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    let mut patterns = Vec::new();
+    for path in files {
+        let contents = fs::read_to_string(&path).unwrap();
+        let parsed = pattern_file::parse(&contents)
+            .unwrap_or_else(|e| panic!("{}:{}: {}", path.display(), e.line, e.message));
+        let expires = parsed.expires.map(pattern_file::Date::end_secs);
+        for entry in parsed.entries {
+            patterns.push((path.clone(), expires, entry));
+        }
+    }
+    patterns
+}
+
+fn generate_blockers(blockers_dir: &Path, domains: &[DomainDir], lists: &[Vec<ModeLists>]) {
+    for (domain, lists) in domains.iter().zip(lists) {
+        let file_path = blockers_dir.join(format!("{}.rs", domain.module()));
+        let mut file = BufWriter::new(File::create(file_path).unwrap());
+
+        // Generate block_<category> and allow, which is checked before
+        // every block list; empty lists never match. An empty category
+        // other than scripts and xhr has no function: block_resource falls
+        // back to scripts for it.
+        for (i, category) in CATEGORIES.iter().chain([&ALLOW_CATEGORY]).enumerate() {
+            let name = if i == CATEGORIES.len() {
+                "allow".to_string()
+            } else {
+                format!("block_{}", category)
+            };
+            let checks = lists[i].checks(domain, category);
+            if checks.is_empty() && !matches!(*category, "scripts" | "xhr" | ALLOW_CATEGORY) {
+                continue;
+            }
+            writeln!(file, "#[inline]").unwrap();
+            if checks.is_empty() {
+                writeln!(file, "pub fn {}(_url: &str) -> bool {{", name).unwrap();
+                writeln!(file, "    false").unwrap();
+            } else {
+                writeln!(file, "pub fn {}(url: &str) -> bool {{", name).unwrap();
+                writeln!(file, "    {}", checks.join("\n        || ")).unwrap();
+            }
+            writeln!(file, "}}\n").unwrap();
+        }
+
+        // Generate block_resource, the only per-type table: one pattern set
+        // per resource type, with the script list covering every non-XHR
+        // type the domain has no list for. intercept_detection is built on
+        // top of it.
+        writeln!(
+            file,
+            "pub fn block_resource(url: &str, resource_type: crate::resource_type::ResourceType) -> bool {{"
+        )
+        .unwrap();
+        writeln!(file, "    use crate::resource_type::ResourceType;").unwrap();
+        writeln!(file, "    if allow(url) {{").unwrap();
+        writeln!(file, "        return false;").unwrap();
+        writeln!(file, "    }}").unwrap();
+        writeln!(file, "    match resource_type {{").unwrap();
+        writeln!(
+            file,
+            "        ResourceType::Xhr | ResourceType::Fetch => block_xhr(url),"
+        )
+        .unwrap();
+        for (resource_type, category) in [
+            ("Stylesheet", "styles"),
+            ("Image", "images"),
+            ("Font", "fonts"),
+            ("Media", "media"),
+            ("WebSocket", "websocket"),
+            ("Ping", "beacon"),
+            ("SubDocument", "iframes"),
+        ] {
+            let i = CATEGORIES.iter().position(|c| *c == category).unwrap();
+            let list = if lists[i].is_empty() {
+                "scripts"
+            } else {
+                category
+            };
+            writeln!(
+                file,
+                "        ResourceType::{} => block_{}(url),",
+                resource_type, list
+            )
+            .unwrap();
+        }
+        writeln!(file, "        ResourceType::Document => false,").unwrap();
+        writeln!(file, "        _ => block_scripts(url),").unwrap();
+        writeln!(file, "    }}").unwrap();
+        writeln!(file, "}}\n").unwrap();
     }
 }
 
-/// indents uppercased
-fn format_ident(name: &str) -> String {
-    name.replace('.', "_").replace('-', "_").to_uppercase()
+fn generate_blockers_mod(blockers_dir: &Path, domains: &[DomainDir]) {
+    let mod_file_path = blockers_dir.join("mod.rs");
+    let mut mod_file = BufWriter::new(File::create(mod_file_path).unwrap());
+
+    for domain in domains {
+        writeln!(mod_file, "pub mod {};", domain.module()).unwrap();
+    }
 }
 
+/// Global lists: `url_patterns/global/<category>/`, the module the static
+/// is generated into, the static's name and its doc line.
+const GLOBAL_LISTS: &[(&str, &str, &str, &str)] = &[
+    (
+        "scripts",
+        "scripts",
+        "URL_IGNORE_TRIE",
+        "Ignore list of scripts.",
+    ),
+    (
+        "embeds",
+        "scripts",
+        "URL_IGNORE_EMBEDED_TRIE",
+        "Ignore list of scripts embedded or font extra.",
+    ),
+    (
+        "script_paths",
+        "scripts",
+        "URL_IGNORE_SCRIPT_BASE_PATHS",
+        "Ignore list of path scripts to ignore for tracking and analytics.",
+    ),
+    (
+        "style_paths",
+        "scripts",
+        "URL_IGNORE_SCRIPT_STYLES_PATHS",
+        "Ignore list of path scripts to ignore for themes.",
+    ),
+    (
+        "css",
+        "scripts",
+        "URL_IGNORE_CSS",
+        "Ignore list of CSS resources.",
+    ),
+    (
+        "paths",
+        "scripts",
+        "URL_IGNORE_TRIE_PATHS",
+        "Ignore list of scripts paths.",
+    ),
+    (
+        "xhr",
+        "xhr",
+        "URL_IGNORE_XHR_TRIE",
+        "Ignore list of XHR urls.",
+    ),
+    (
+        "xhr_media",
+        "xhr",
+        "URL_IGNORE_XHR_MEDIA_TRIE",
+        "Ignore list of XHR urls for media.",
+    ),
+];
+
+/// Per-domain pattern categories: subdirectories of
+/// `url_patterns/domains/<domain>/`. Each becomes a `block_<category>`
+/// function in the domain's blockers module.
+const CATEGORIES: &[&str] = &[
+    "scripts",
+    "xhr",
+    "styles",
+    "images",
+    "fonts",
+    "media",
+    "websocket",
+    "beacon",
+    "iframes",
+];
+
+/// Per-domain allow list directory: overrides the domain's block categories
+/// and the global tries.
+const ALLOW_CATEGORY: &str = "allow";
+
 #[cfg(feature = "adblock_easylist")]
 mod easylist {
     use std::io::{Read, Write};
@@ -0,0 +1,169 @@
+//! Identifiers for `url_patterns/domains/<domain>/` directories.
+//!
+//! Shared by `build.rs` and `tests/build_domains.rs`. Every directory gets
+//! one snake-case identifier derived from its full name (`web.archive.org`
+//! → `web_archive_org`), which names its blockers module and its tries, and
+//! one `NetworkInterceptManager` variant from the name without its last
+//! label (`WebArchive`). Directories whose short variants clash use the
+//! full name instead (`x.com`, `x.org` → `XCom`, `XOrg`). Two directories
+//! mapping to the same identifier are an error.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Variant the generated enum reserves for unmatched hosts.
+const RESERVED_VARIANT: &str = "Unknown";
+
+/// One domain directory and the names generated for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainDir {
+    /// Path to the directory.
+    pub path: PathBuf,
+    /// Directory name, lowercased: the `DOMAIN_MAP` key.
+    pub name: String,
+    /// Snake-case identifier from the full name.
+    pub ident: String,
+    /// `NetworkInterceptManager` variant.
+    pub variant: String,
+}
+
+impl DomainDir {
+    /// Name of the generated `blockers` module.
+    pub fn module(&self) -> String {
+        format!("{}_blockers", self.ident)
+    }
+
+    /// Name of the generated URL-prefix static for `category`.
+    pub fn trie(&self, category: &str) -> String {
+        format!("{}_{}_TRIE", self.ident, category).to_uppercase()
+    }
+
+    /// Name of the generated `@host` static for `category`.
+    pub fn hosts(&self, category: &str) -> String {
+        format!("{}_{}_HOSTS", self.ident, category).to_uppercase()
+    }
+
+    /// Name of the generated `@substring` static for `category`.
+    pub fn substrings(&self, category: &str) -> String {
+        format!("{}_{}_SUBSTRINGS", self.ident, category).to_uppercase()
+    }
+}
+
+/// Every subdirectory of `pattern_dir`, sorted by name.
+///
+/// Fails on names that aren't letters, digits, `.` and `-`, and on two
+/// directories sharing an identifier. Directories sharing a short variant
+/// are given variants from their full names.
+pub fn scan(pattern_dir: &Path) -> Result<Vec<DomainDir>, String> {
+    let entries =
+        fs::read_dir(pattern_dir).map_err(|e| format!("{}: {}", pattern_dir.display(), e))?;
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("{}: {}", pattern_dir.display(), e))?
+            .path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("{}: directory name is not UTF-8", path.display()))?
+            .to_lowercase();
+        let snake = ident(&name).ok_or_else(|| {
+            format!(
+                "{}: domain directories may only contain letters, digits, '.' and '-'",
+                path.display()
+            )
+        })?;
+        let stem = match name.rfind('.') {
+            Some(dot) => &name[..dot],
+            None => &name,
+        };
+        let variant = upper_camel(&ident(stem).unwrap_or_else(|| snake.clone()));
+        dirs.push(DomainDir {
+            path,
+            name,
+            ident: snake,
+            variant,
+        });
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut short: HashMap<String, usize> = HashMap::new();
+    for dir in &dirs {
+        *short.entry(dir.variant.clone()).or_default() += 1;
+    }
+    for dir in &mut dirs {
+        if short[&dir.variant] > 1 {
+            dir.variant = upper_camel(&dir.ident);
+        }
+    }
+
+    let mut idents: HashMap<&str, &DomainDir> = HashMap::new();
+    let mut variants: HashMap<&str, &DomainDir> = HashMap::new();
+    for dir in &dirs {
+        if dir.variant == RESERVED_VARIANT {
+            return Err(format!(
+                "{}: variant `{}` is reserved; rename the directory",
+                dir.path.display(),
+                RESERVED_VARIANT
+            ));
+        }
+        if let Some(other) = idents.insert(&dir.ident, dir) {
+            return Err(collision(other, dir, "identifier", &dir.ident));
+        }
+        if let Some(other) = variants.insert(&dir.variant, dir) {
+            return Err(collision(other, dir, "variant", &dir.variant));
+        }
+    }
+    Ok(dirs)
+}
+
+/// `web.archive.org` → `web_archive_org`. Runs of `.` and `-` collapse to
+/// one `_` (`xn--p1ai` → `xn_p1ai`) and names starting with a digit get a
+/// `domain_` prefix. `None` for empty labels or characters other than ASCII
+/// letters, digits, `.` and `-`.
+pub fn ident(name: &str) -> Option<String> {
+    if name.split('.').any(str::is_empty) {
+        return None;
+    }
+    let mut out = String::with_capacity(name.len() + 7);
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        out.push_str("domain_");
+    }
+    for c in name.chars() {
+        match c {
+            'a'..='z' | '0'..='9' => out.push(c),
+            'A'..='Z' => out.push(c.to_ascii_lowercase()),
+            '.' | '-' if out.ends_with('_') => {}
+            '.' | '-' => out.push('_'),
+            _ => return None,
+        }
+    }
+    Some(out.trim_end_matches('_').to_string())
+}
+
+/// `web_archive` → `WebArchive`.
+pub fn upper_camel(ident: &str) -> String {
+    let mut out = String::with_capacity(ident.len());
+    for part in ident.split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+    out
+}
+
+fn collision(a: &DomainDir, b: &DomainDir, what: &str, value: &str) -> String {
+    format!(
+        "domain directories `{}` and `{}` both map to {} `{}`; rename one of them",
+        a.path.display(),
+        b.path.display(),
+        what,
+        value
+    )
+}
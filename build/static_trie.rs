@@ -0,0 +1,124 @@
+//! Packs pattern lists into the flat arrays behind `trie::StaticTrie`.
+//!
+//! Shared by `build.rs` and `tests/build_tries.rs`. Nodes are numbered in
+//! depth-first preorder with edges sorted by byte, so a lookup walks
+//! forward through the arrays. Node `i`'s edges are
+//! `keys[offsets[i]..offsets[i + 1]]`, leading to the nodes in the same range
+//! of `children`; `ends[i]` marks where a pattern ends. `expires` lists
+//! `(node, unix_secs)` for patterns that stop matching at `unix_secs`.
+
+use std::collections::BTreeMap;
+
+/// A packed trie, ready to be written out as Rust source.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackedTrie {
+    pub keys: Vec<u8>,
+    pub children: Vec<u32>,
+    pub offsets: Vec<u32>,
+    pub ends: Vec<bool>,
+    pub expires: Vec<(u32, u64)>,
+}
+
+#[derive(Default)]
+struct Node {
+    edges: BTreeMap<u8, usize>,
+    /// `Some` where a pattern ends, holding its expiry cutoff if any.
+    end: Option<Option<u64>>,
+}
+
+impl PackedTrie {
+    /// Pack `patterns`. Empty patterns and duplicates are dropped.
+    pub fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        Self::with_expiry(patterns.into_iter().map(|p| (p, None)))
+    }
+
+    /// Pack `patterns`, each with the Unix time it expires at, if any.
+    /// A duplicate keeps the later cutoff; one without a cutoff never
+    /// expires.
+    pub fn with_expiry<'a>(patterns: impl IntoIterator<Item = (&'a str, Option<u64>)>) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (pattern, expires) in patterns {
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut idx = 0;
+            for &b in pattern.as_bytes() {
+                idx = match nodes[idx].edges.get(&b) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[idx].edges.insert(b, child);
+                        child
+                    }
+                };
+            }
+            let end = &mut nodes[idx].end;
+            *end = match (*end, expires) {
+                (Some(None), _) | (_, None) => Some(None),
+                (Some(Some(a)), Some(b)) => Some(Some(a.max(b))),
+                (None, Some(b)) => Some(Some(b)),
+            };
+        }
+
+        // Renumber in preorder.
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut stack = vec![0];
+        while let Some(idx) = stack.pop() {
+            order.push(idx);
+            stack.extend(nodes[idx].edges.values().rev());
+        }
+        let mut number = vec![0u32; nodes.len()];
+        for (new, &old) in order.iter().enumerate() {
+            number[old] = new as u32;
+        }
+
+        let mut packed = PackedTrie {
+            offsets: vec![0],
+            ..PackedTrie::default()
+        };
+        for &old in &order {
+            let node = &nodes[old];
+            for (&byte, &child) in &node.edges {
+                packed.keys.push(byte);
+                packed.children.push(number[child]);
+            }
+            packed.offsets.push(packed.keys.len() as u32);
+            packed.ends.push(node.end.is_some());
+            if let Some(Some(at)) = node.end {
+                packed.expires.push((packed.ends.len() as u32 - 1, at));
+            }
+        }
+        packed
+    }
+
+    /// A `StaticTrie::from_parts` call expression over array literals,
+    /// followed by `.with_expiry(..)` if any pattern expires.
+    pub fn to_rust(&self) -> String {
+        fn list<T: ToString>(items: impl IntoIterator<Item = T>) -> String {
+            items
+                .into_iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        let mut out = format!(
+            "crate::trie::StaticTrie::from_parts(&[{}], &[{}], &[{}], &[{}])",
+            list(self.keys.iter()),
+            list(self.children.iter()),
+            list(self.offsets.iter()),
+            list(self.ends.iter()),
+        );
+        if !self.expires.is_empty() {
+            out += &format!(
+                ".with_expiry(&[{}])",
+                list(
+                    self.expires
+                        .iter()
+                        .map(|(node, at)| format!("({},{})", node, at))
+                )
+            );
+        }
+        out
+    }
+}
@@ -1,3 +1,4 @@
+use crate::substring::SubstringMatcher;
 use std::sync::LazyLock;
 
 pub static ADBLOCK_PATTERNS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
@@ -49,8 +50,13 @@ pub static ADBLOCK_PATTERNS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
     ]
 });
 
+/// [`ADBLOCK_PATTERNS`] as a substring matcher for full URLs.
+pub static ADBLOCK_PATTERNS_MATCHER: LazyLock<SubstringMatcher> =
+    LazyLock::new(|| SubstringMatcher::new(ADBLOCK_PATTERNS.iter().copied()));
+
 #[cfg(feature = "adblock")]
 pub mod engine {
+    use crate::resource_type::ResourceType;
     use std::sync::Arc;
 
     /// Well-known filter list URLs for callers to fetch externally.
@@ -99,7 +105,8 @@ pub mod engine {
         /// Check whether `url` should be blocked.
         ///
         /// - `source_url`: the page URL that initiated the request.
-        /// - `request_type`: resource type (`"script"`, `"xhr"`, `"image"`, etc.).
+        /// - `request_type`: resource type (`"script"`, `"xhr"`, `"image"`, etc.),
+        ///   passed to the engine as is.
         pub fn should_block(&self, url: &str, source_url: &str, request_type: &str) -> bool {
             match adblock::request::Request::new(url, source_url, request_type) {
                 Ok(request) => self.inner.check_network_request(&request).matched,
@@ -107,6 +114,18 @@ pub mod engine {
             }
         }
 
+        /// [`should_block`](Self::should_block) for a [`ResourceType`].
+        /// Types the enum doesn't name, such as `"object"` or
+        /// `"csp_report"`, need the string form.
+        pub fn should_block_resource(
+            &self,
+            url: &str,
+            source_url: &str,
+            resource_type: ResourceType,
+        ) -> bool {
+            self.should_block(url, source_url, resource_type.as_adblock_str())
+        }
+
         /// Full blocker result for advanced use (redirect, exception, etc.).
         pub fn check_request(
             &self,
@@ -119,6 +138,16 @@ pub mod engine {
                 .map(|req| self.inner.check_network_request(&req))
         }
 
+        /// [`check_request`](Self::check_request) for a [`ResourceType`].
+        pub fn check_resource_request(
+            &self,
+            url: &str,
+            source_url: &str,
+            resource_type: ResourceType,
+        ) -> Option<adblock::blocker::BlockerResult> {
+            self.check_request(url, source_url, resource_type.as_adblock_str())
+        }
+
         /// Serialize the engine to bytes for persistence / caching.
         pub fn serialize(&self) -> Vec<u8> {
             self.inner.serialize()
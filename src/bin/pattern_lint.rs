@@ -0,0 +1,76 @@
+//! Report shadowed, duplicated and over-broad patterns.
+//!
+//! ```text
+//! pattern_lint [PATTERN_DIR] [--min-len N]
+//! ```
+//!
+//! Lints the `url_patterns` tree at `PATTERN_DIR` (default: `url_patterns`
+//! in the current directory, then in the crate it was built from) together
+//! with `ADBLOCK_PATTERNS`. Without a tree, the compiled-in global tries are
+//! linted instead. Exits with status 1 when anything is reported.
+
+use spider_network_blocker::lint::{self, DEFAULT_MIN_LEN};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut dir = None;
+    let mut min_len = DEFAULT_MIN_LEN;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--min-len" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => min_len = n,
+                None => return usage("--min-len needs a number"),
+            },
+            "-h" | "--help" => return usage(""),
+            _ if dir.is_none() && !arg.starts_with('-') => dir = Some(PathBuf::from(arg)),
+            _ => return usage(&format!("unexpected argument {:?}", arg)),
+        }
+    }
+
+    let dir = dir.or_else(|| {
+        [
+            PathBuf::from("url_patterns"),
+            PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/url_patterns")),
+        ]
+        .into_iter()
+        .find(|d| d.is_dir())
+    });
+    let mut lists = match &dir {
+        Some(dir) => match lint::load_pattern_dir(dir) {
+            Ok(lists) => lists,
+            Err(e) => {
+                eprintln!("pattern_lint: {}: {}", dir.display(), e);
+                return ExitCode::from(2);
+            }
+        },
+        None => lint::global_tries(),
+    };
+    lists.push(lint::adblock_patterns());
+
+    let findings = lint::lint(&lists, min_len);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    let patterns: usize = lists.iter().map(|l| l.patterns.len()).sum();
+    eprintln!(
+        "pattern_lint: {} finding(s) in {} patterns across {} lists",
+        findings.len(),
+        patterns,
+        lists.len()
+    );
+    if findings.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn usage(error: &str) -> ExitCode {
+    if !error.is_empty() {
+        eprintln!("pattern_lint: {}", error);
+    }
+    eprintln!("usage: pattern_lint [PATTERN_DIR] [--min-len N]");
+    ExitCode::from(2)
+}
@@ -0,0 +1,428 @@
+//! One configurable decision point over every built-in list.
+//!
+//! [`NetworkBlocker`] applies the lists in the same order and with the same
+//! path slicing as spider's request interception, so callers no longer wire
+//! `URL_IGNORE_TRIE`, the path tries, the XHR tries and the per-domain
+//! managers together by hand:
+//!
+//! 1. Document requests, and URLs on the page domain's `allow` list, are
+//!    never blocked.
+//! 2. With `ignore_visuals`, images, media and fonts are blocked outright.
+//! 3. XHR / Fetch: `URL_IGNORE_XHR_TRIE`, then `URL_IGNORE_XHR_MEDIA_TRIE`
+//!    (with `ignore_visuals`).
+//! 4. Everything else: `URL_IGNORE_TRIE` (with `block_analytics`),
+//!    `URL_IGNORE_EMBEDED_TRIE` (with `block_embeds`), `URL_IGNORE_CSS`
+//!    (with `ignore_visuals`), then the path after the host against
+//!    `URL_IGNORE_SCRIPT_BASE_PATHS`, `URL_IGNORE_SCRIPT_STYLES_PATHS` (with
+//!    `ignore_visuals`) and `URL_IGNORE_TRIE_PATHS`; the last path segment is
+//!    also checked against `URL_IGNORE_TRIE_PATHS`.
+//! 5. The page domain's [`NetworkInterceptManager`] pattern set for the
+//!    resource type (the script list for visual types without
+//!    `ignore_visuals`), or its
+//!    [`DomainProfile`](crate::domain_profiles::DomainProfile) when one is
+//!    registered with [`NetworkBlocker::with_profiles`].
+//! 6. Extra matchers, in the order they were added.
+//! 7. The adblock engine, if one is set.
+
+use crate::domain_profiles::{DomainProfiles, ResolvedProfile};
+use crate::host_trie::extract_host;
+use crate::intercept_manager::NetworkInterceptManager;
+use crate::matcher::{RequestContext, UrlMatcher};
+use crate::resource_type::ResourceType;
+use crate::scripts::{
+    URL_IGNORE_CSS, URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_SCRIPT_BASE_PATHS,
+    URL_IGNORE_SCRIPT_STYLES_PATHS, URL_IGNORE_TRIE, URL_IGNORE_TRIE_PATHS,
+};
+use crate::xhr::{URL_IGNORE_XHR_MEDIA_TRIE, URL_IGNORE_XHR_TRIE};
+use std::sync::Arc;
+
+/// Which rule blocked a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockReason {
+    /// Image, media or font request while ignoring visuals.
+    Visual,
+    /// `URL_IGNORE_TRIE`.
+    Tracker,
+    /// `URL_IGNORE_EMBEDED_TRIE`.
+    Embed,
+    /// `URL_IGNORE_CSS`.
+    Css,
+    /// `URL_IGNORE_SCRIPT_BASE_PATHS` or `URL_IGNORE_TRIE_PATHS`.
+    ScriptPath,
+    /// `URL_IGNORE_SCRIPT_STYLES_PATHS`.
+    StylePath,
+    /// `URL_IGNORE_XHR_TRIE`.
+    Xhr,
+    /// `URL_IGNORE_XHR_MEDIA_TRIE`.
+    XhrMedia,
+    /// The page domain's [`NetworkInterceptManager`] or registered profile
+    /// lists.
+    Domain,
+    /// The extra matcher at this index.
+    Custom(usize),
+    /// The adblock engine.
+    Adblock,
+}
+
+/// Decides requests against all built-in lists. See the module docs for the
+/// order rules are applied in.
+///
+/// ```rust
+/// use spider_network_blocker::blocker::NetworkBlocker;
+/// use spider_network_blocker::matcher::RequestContext;
+///
+/// let blocker = NetworkBlocker::new().with_ignore_visuals(true);
+/// let request = RequestContext::new("https://www.googletagmanager.com/gtm.js")
+///     .with_source_url("https://example.com/")
+///     .with_resource_type("Script");
+/// assert!(blocker.should_block(&request));
+/// ```
+pub struct NetworkBlocker {
+    block_analytics: bool,
+    ignore_visuals: bool,
+    block_embeds: bool,
+    /// Fixed page domain; `None` resolves it from each request's source URL.
+    page: Option<NetworkInterceptManager>,
+    /// Runtime profiles consulted when resolving the page domain.
+    profiles: Option<Arc<DomainProfiles>>,
+    extra: Vec<Box<dyn UrlMatcher + Send + Sync>>,
+    #[cfg(feature = "adblock")]
+    adblock: Option<Arc<crate::adblock::engine::AdblockEngine>>,
+}
+
+impl Default for NetworkBlocker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkBlocker {
+    /// Block analytics and trackers; keep visuals and embeds.
+    pub fn new() -> Self {
+        Self {
+            block_analytics: true,
+            ignore_visuals: false,
+            block_embeds: false,
+            page: None,
+            profiles: None,
+            extra: Vec::new(),
+            #[cfg(feature = "adblock")]
+            adblock: None,
+        }
+    }
+
+    /// Check `URL_IGNORE_TRIE`. On by default.
+    pub fn with_block_analytics(mut self, block: bool) -> Self {
+        self.block_analytics = block;
+        self
+    }
+
+    /// Block images, media, fonts and theme styles.
+    pub fn with_ignore_visuals(mut self, ignore: bool) -> Self {
+        self.ignore_visuals = ignore;
+        self
+    }
+
+    /// Block video, map and widget embeds (`URL_IGNORE_EMBEDED_TRIE`).
+    pub fn with_block_embeds(mut self, block: bool) -> Self {
+        self.block_embeds = block;
+        self
+    }
+
+    /// Use `manager` for every request instead of resolving it from each
+    /// request's source URL. Set this when the blocker serves one page.
+    pub fn with_page(mut self, manager: NetworkInterceptManager) -> Self {
+        self.page = Some(manager);
+        self
+    }
+
+    /// Resolve page domains against `profiles` as well as the compiled-in
+    /// variants. Ignored when [`with_page`](Self::with_page) fixes the page.
+    pub fn with_profiles(mut self, profiles: Arc<DomainProfiles>) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Check `matcher` after the built-in lists, e.g. a
+    /// [`DynamicBlockList`](crate::dynamic_blocklist::DynamicBlockList).
+    pub fn with_matcher(mut self, matcher: impl UrlMatcher + Send + Sync + 'static) -> Self {
+        self.extra.push(Box::new(matcher));
+        self
+    }
+
+    /// Check `engine` last.
+    #[cfg(feature = "adblock")]
+    pub fn with_adblock(mut self, engine: Arc<crate::adblock::engine::AdblockEngine>) -> Self {
+        self.adblock = Some(engine);
+        self
+    }
+
+    /// `true` if `request` should be blocked.
+    #[inline]
+    pub fn should_block(&self, request: &RequestContext<'_>) -> bool {
+        self.decide(request).is_some()
+    }
+
+    /// The rule that blocks `request`, or `None` to let it through.
+    pub fn decide(&self, request: &RequestContext<'_>) -> Option<BlockReason> {
+        let url = request.url;
+        let kind = request.resource_type;
+
+        let page = self.page_profile(request);
+        if kind == ResourceType::Document || page.is_allowed(url) {
+            return None;
+        }
+        if self.ignore_visuals
+            && matches!(
+                kind,
+                ResourceType::Image | ResourceType::Media | ResourceType::Font
+            )
+        {
+            return Some(BlockReason::Visual);
+        }
+
+        if kind.is_xhr() {
+            if URL_IGNORE_XHR_TRIE.contains_prefix(url) {
+                return Some(BlockReason::Xhr);
+            }
+            if self.ignore_visuals && URL_IGNORE_XHR_MEDIA_TRIE.contains_prefix(url) {
+                return Some(BlockReason::XhrMedia);
+            }
+        } else if let Some(reason) = self.decide_static(url) {
+            return Some(reason);
+        }
+
+        if page.intercept_resource(url, kind.domain_list_type(self.ignore_visuals)) {
+            return Some(BlockReason::Domain);
+        }
+        if let Some(i) = self.extra.iter().position(|m| m.matches(request)) {
+            return Some(BlockReason::Custom(i));
+        }
+        #[cfg(feature = "adblock")]
+        if let Some(engine) = &self.adblock {
+            if engine.matches(request) {
+                return Some(BlockReason::Adblock);
+            }
+        }
+        None
+    }
+
+    /// Global lists for scripts, styles and other non-XHR requests.
+    fn decide_static(&self, url: &str) -> Option<BlockReason> {
+        if self.block_analytics && URL_IGNORE_TRIE.contains_prefix(url) {
+            return Some(BlockReason::Tracker);
+        }
+        if self.block_embeds && URL_IGNORE_EMBEDED_TRIE.contains_prefix(url) {
+            return Some(BlockReason::Embed);
+        }
+        if self.ignore_visuals && URL_IGNORE_CSS.contains_prefix(url) {
+            return Some(BlockReason::Css);
+        }
+        let path = base_path(url)?;
+        if URL_IGNORE_SCRIPT_BASE_PATHS.contains_prefix(path) {
+            return Some(BlockReason::ScriptPath);
+        }
+        if self.ignore_visuals && URL_IGNORE_SCRIPT_STYLES_PATHS.contains_prefix(path) {
+            return Some(BlockReason::StylePath);
+        }
+        if URL_IGNORE_TRIE_PATHS.contains_prefix(path)
+            || URL_IGNORE_TRIE_PATHS.contains_prefix(last_segment(path))
+        {
+            return Some(BlockReason::ScriptPath);
+        }
+        None
+    }
+
+    fn page_profile(&self, request: &RequestContext<'_>) -> ResolvedProfile {
+        if let Some(manager) = self.page {
+            return ResolvedProfile::Builtin(manager);
+        }
+        let Some(host) = extract_host(request.source_url) else {
+            return ResolvedProfile::default();
+        };
+        match &self.profiles {
+            Some(profiles) => profiles.resolve(host),
+            None => ResolvedProfile::Builtin(NetworkInterceptManager::from_host(host)),
+        }
+    }
+}
+
+impl UrlMatcher for NetworkBlocker {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.should_block(request)
+    }
+}
+
+/// The part of `url` after `scheme://host/`, if non-empty.
+fn base_path(url: &str) -> Option<&str> {
+    let start = url.find("//")? + 2;
+    let slash = url[start..].find('/')?;
+    let path = &url[start + slash + 1..];
+    (!path.is_empty()).then_some(path)
+}
+
+/// Last `/`-separated segment of `path`, query included.
+fn last_segment(path: &str) -> &str {
+    let end = path.find(['?', '#']).unwrap_or(path.len());
+    match path[..end].rfind('/') {
+        Some(i) => &path[i + 1..],
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic_blocklist::DynamicBlockList;
+
+    fn script(url: &str) -> RequestContext<'_> {
+        RequestContext::new(url).with_resource_type("Script")
+    }
+
+    #[test]
+    fn path_slicing() {
+        assert_eq!(
+            base_path("https://example.com/wp-content/a.js"),
+            Some("wp-content/a.js")
+        );
+        assert_eq!(base_path("https://example.com/"), None);
+        assert_eq!(base_path("https://example.com"), None);
+        assert_eq!(
+            last_segment("assets/js/tracking.min.js?v=1"),
+            "tracking.min.js?v=1"
+        );
+        assert_eq!(last_segment("ads.js"), "ads.js");
+    }
+
+    #[test]
+    fn canonical_order() {
+        let blocker = NetworkBlocker::new();
+        assert_eq!(
+            blocker.decide(&script("https://www.googletagmanager.com/gtm.js")),
+            Some(BlockReason::Tracker)
+        );
+        assert_eq!(
+            blocker.decide(&script(
+                "https://example.com/wp-content/plugins/cookie-law-info/a.js"
+            )),
+            Some(BlockReason::ScriptPath)
+        );
+        assert_eq!(
+            blocker.decide(&script("https://example.com/assets/tracking.min.js")),
+            Some(BlockReason::ScriptPath)
+        );
+        assert_eq!(blocker.decide(&script("https://example.com/app.js")), None);
+        // Documents are never blocked.
+        let doc = RequestContext::new("https://www.googletagmanager.com/ns.html")
+            .with_resource_type("Document");
+        assert_eq!(blocker.decide(&doc), None);
+    }
+
+    #[test]
+    fn toggles() {
+        let embed = script("https://www.youtube.com/embed/abc");
+        let theme = script("https://example.com/wp-content/themes/x/a.js");
+        let image = RequestContext::new("https://example.com/a.png")
+            .with_resource_type(ResourceType::Image);
+
+        let plain = NetworkBlocker::new();
+        assert_eq!(plain.decide(&embed), None);
+        assert_eq!(plain.decide(&theme), None);
+        assert_eq!(plain.decide(&image), None);
+
+        let strict = NetworkBlocker::new()
+            .with_block_embeds(true)
+            .with_ignore_visuals(true);
+        assert_eq!(strict.decide(&embed), Some(BlockReason::Embed));
+        assert_eq!(strict.decide(&theme), Some(BlockReason::StylePath));
+        assert_eq!(strict.decide(&image), Some(BlockReason::Visual));
+
+        let lax = NetworkBlocker::new().with_block_analytics(false);
+        assert_eq!(
+            lax.decide(&script("https://www.googletagmanager.com/gtm.js")),
+            None
+        );
+    }
+
+    #[test]
+    fn xhr_lists() {
+        let blocker = NetworkBlocker::new().with_ignore_visuals(true);
+        let xhr = RequestContext::new("https://analytics.google.com/g/collect?v=2")
+            .with_resource_type("XHR");
+        assert_eq!(blocker.decide(&xhr), Some(BlockReason::Xhr));
+        let media =
+            RequestContext::new("https://open.spotify.com/track/1").with_resource_type("Fetch");
+        assert_eq!(blocker.decide(&media), Some(BlockReason::XhrMedia));
+        // Script-only lists don't apply to XHR.
+        let gtm = RequestContext::new("https://www.googletagmanager.com/gtm.js")
+            .with_resource_type("XHR");
+        assert_eq!(blocker.decide(&gtm), None);
+    }
+
+    #[test]
+    fn page_domain_and_extra_matchers() {
+        let ttwid = script("https://www.tiktok.com/ttwid/check");
+        assert_eq!(NetworkBlocker::new().decide(&ttwid), None);
+        assert_eq!(
+            NetworkBlocker::new().decide(&ttwid.with_source_url("https://www.tiktok.com/@user")),
+            Some(BlockReason::Domain)
+        );
+        assert_eq!(
+            NetworkBlocker::new()
+                .with_page(NetworkInterceptManager::Tiktok)
+                .decide(&ttwid),
+            Some(BlockReason::Domain)
+        );
+
+        // The page domain's allow list beats every block list.
+        let aui = script("https://images-na.ssl-images-amazon.com/images/G/01/AUIClients/A.js");
+        let blocker = NetworkBlocker::new().with_matcher(DynamicBlockList::from_patterns([
+            "https://images-na.ssl-images-amazon.com/",
+        ]));
+        assert_eq!(blocker.decide(&aui), Some(BlockReason::Custom(0)));
+        let blocker = blocker.with_page(NetworkInterceptManager::Amazon);
+        assert_eq!(blocker.decide(&aui), None);
+
+        let blocker = NetworkBlocker::new()
+            .with_matcher(DynamicBlockList::from_patterns(["https://a.example.com/"]))
+            .with_matcher(DynamicBlockList::from_patterns(["https://b.example.com/"]));
+        assert_eq!(
+            blocker.decide(&script("https://b.example.com/x.js")),
+            Some(BlockReason::Custom(1))
+        );
+    }
+
+    #[test]
+    fn runtime_profiles() {
+        use crate::domain_profiles::{DomainProfile, PatternCategory};
+
+        let profiles = Arc::new(DomainProfiles::new());
+        profiles.register(
+            DomainProfile::new("example.co.uk")
+                .with_patterns(PatternCategory::Scripts, ["https://cdn.example.co.uk/ads/"])
+                .with_patterns(
+                    PatternCategory::Allow,
+                    ["https://images-na.ssl-images-amazon.com/"],
+                ),
+        );
+        let blocker = NetworkBlocker::new().with_profiles(Arc::clone(&profiles));
+        let ad = script("https://cdn.example.co.uk/ads/a.js");
+        assert_eq!(NetworkBlocker::new().decide(&ad), None);
+        assert_eq!(
+            blocker.decide(&ad.with_source_url("https://www.example.co.uk/")),
+            Some(BlockReason::Domain)
+        );
+        // The profile's allow list is honoured too.
+        let aui = script("https://images-na.ssl-images-amazon.com/images/G/01/AUIClients/A.js")
+            .with_source_url("https://www.example.co.uk/");
+        let blocker = blocker.with_matcher(DynamicBlockList::from_patterns([
+            "https://images-na.ssl-images-amazon.com/",
+        ]));
+        assert_eq!(blocker.decide(&aui), None);
+        // Compiled-in variants still resolve through the registry.
+        let ttwid = script("https://www.tiktok.com/ttwid/check")
+            .with_source_url("https://www.tiktok.com/@user");
+        assert_eq!(blocker.decide(&ttwid), Some(BlockReason::Domain));
+    }
+}
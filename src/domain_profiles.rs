@@ -0,0 +1,854 @@
+//! Domain profiles registered at runtime.
+//!
+//! A [`DomainProfile`] is the runtime twin of a compiled-in
+//! `url_patterns/domains/<host>/` directory: a host plus one pattern list per
+//! category. [`DomainProfiles`] holds them next to the compiled-in
+//! [`NetworkInterceptManager`] variants and resolves a page host against
+//! both with the same walk — the host, then each parent down to the
+//! registrable domain — where a registered profile wins over a compiled-in
+//! variant for the same name.
+//!
+//! Profiles load from the same directory layout the build script reads, or
+//! from a config file:
+//!
+//! ```text
+//! # Lines are `category = line`; `[host]` starts a profile.
+//! [amazon.co.uk]
+//! scripts = https://www.amazon.co.uk/gp/ads/
+//! scripts = @host
+//! scripts = amazon-adsystem.com
+//! xhr = https://www.amazon.co.uk/rd/uedata
+//! allow = https://m.media-amazon.com/images/G/
+//! ```
+//!
+//! Each value is one line of the [`pattern_file`] format, and a section's
+//! values for one category are parsed as one pattern file: a `@host`
+//! directive applies to that category's later lines, and `!allow` goes to
+//! the allow list.
+
+use crate::host_trie::HostTrie;
+use crate::intercept_manager::{resolve_host, NetworkInterceptManager};
+use crate::matcher::{RequestContext, UrlMatcher};
+use crate::pattern_file::{self, MatchMode};
+use crate::resource_type::ResourceType;
+use crate::substring::SubstringMatcher;
+use crate::trie::Trie;
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+/// One pattern list of a domain profile, named like its directory under
+/// `url_patterns/domains/<host>/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternCategory {
+    /// `scripts/` — scripts, and every type without a list of its own.
+    Scripts,
+    /// `xhr/` — XHR and Fetch.
+    Xhr,
+    /// `styles/` — stylesheets.
+    Styles,
+    /// `images/` — images.
+    Images,
+    /// `fonts/` — web fonts.
+    Fonts,
+    /// `media/` — audio, video and text tracks.
+    Media,
+    /// `websocket/` — WebSocket handshakes.
+    WebSocket,
+    /// `beacon/` — beacons and pings.
+    Beacon,
+    /// `iframes/` — frame documents.
+    Iframes,
+    /// `allow/` — overrides the other lists and the global tries.
+    Allow,
+}
+
+impl PatternCategory {
+    /// Every category, in directory-table order.
+    pub const ALL: [PatternCategory; 10] = [
+        PatternCategory::Scripts,
+        PatternCategory::Xhr,
+        PatternCategory::Styles,
+        PatternCategory::Images,
+        PatternCategory::Fonts,
+        PatternCategory::Media,
+        PatternCategory::WebSocket,
+        PatternCategory::Beacon,
+        PatternCategory::Iframes,
+        PatternCategory::Allow,
+    ];
+
+    /// Directory name, e.g. `"scripts"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PatternCategory::Scripts => "scripts",
+            PatternCategory::Xhr => "xhr",
+            PatternCategory::Styles => "styles",
+            PatternCategory::Images => "images",
+            PatternCategory::Fonts => "fonts",
+            PatternCategory::Media => "media",
+            PatternCategory::WebSocket => "websocket",
+            PatternCategory::Beacon => "beacon",
+            PatternCategory::Iframes => "iframes",
+            PatternCategory::Allow => "allow",
+        }
+    }
+
+    /// Parse a directory name. Unknown names return `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == name)
+    }
+
+    /// The list checked for requests of `resource_type`, or `None` for
+    /// documents, which no domain list blocks.
+    pub fn for_resource(resource_type: ResourceType) -> Option<Self> {
+        Some(match resource_type {
+            ResourceType::Xhr | ResourceType::Fetch => PatternCategory::Xhr,
+            ResourceType::Stylesheet => PatternCategory::Styles,
+            ResourceType::Image => PatternCategory::Images,
+            ResourceType::Font => PatternCategory::Fonts,
+            ResourceType::Media => PatternCategory::Media,
+            ResourceType::WebSocket => PatternCategory::WebSocket,
+            ResourceType::Ping => PatternCategory::Beacon,
+            ResourceType::SubDocument => PatternCategory::Iframes,
+            ResourceType::Document => return None,
+            _ => PatternCategory::Scripts,
+        })
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One category's patterns, one matcher per [`MatchMode`].
+#[derive(Debug, Default)]
+struct PatternList {
+    prefixes: Trie,
+    hosts: HostTrie,
+    /// Source of `matcher`, which is rebuilt on the first lookup after an
+    /// insert.
+    substrings: Trie,
+    matcher: OnceLock<SubstringMatcher>,
+}
+
+impl PatternList {
+    fn insert(&mut self, mode: MatchMode, pattern: &str) {
+        match mode {
+            MatchMode::Prefix => self.prefixes.insert(pattern),
+            MatchMode::Host => self.hosts.insert(pattern),
+            MatchMode::Substring => {
+                self.substrings.insert(pattern);
+                self.matcher = OnceLock::new();
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.prefixes.len() + self.hosts.len() + self.substrings.len()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.prefixes.shrink_to_fit();
+        self.hosts.shrink_to_fit();
+        self.substrings.shrink_to_fit();
+        self.substring_matcher();
+    }
+
+    fn substring_matcher(&self) -> &SubstringMatcher {
+        self.matcher
+            .get_or_init(|| SubstringMatcher::from_trie(&self.substrings))
+    }
+
+    #[inline]
+    fn matches(&self, url: &str) -> bool {
+        self.prefixes.contains_prefix(url)
+            || (!self.hosts.is_empty() && self.hosts.contains_url(url))
+            || (!self.substrings.is_empty() && self.substring_matcher().is_match(url))
+    }
+}
+
+/// A host and its per-category pattern lists, with the same semantics as a
+/// compiled-in [`NetworkInterceptManager`] variant.
+#[derive(Debug, Default)]
+pub struct DomainProfile {
+    host: String,
+    lists: [PatternList; PatternCategory::ALL.len()],
+}
+
+impl DomainProfile {
+    /// Empty profile for `host`. ASCII case and a trailing dot are
+    /// normalized away.
+    pub fn new(host: &str) -> Self {
+        let host = host.trim();
+        let host = host.strip_suffix('.').unwrap_or(host);
+        Self {
+            host: host.to_ascii_lowercase(),
+            lists: Default::default(),
+        }
+    }
+
+    /// Load `dir` laid out like `url_patterns/domains/<host>/`: the
+    /// directory name is the host and each category subdirectory holds
+    /// pattern files in the [`pattern_file`] format. `!allow` lines go to
+    /// the allow list, files past their `expires:` date are skipped and
+    /// unknown subdirectories are ignored.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] naming the file and line
+    /// of the first malformed line.
+    pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let host = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| invalid(format!("{}: not a host directory", dir.display())))?;
+        let mut profile = Self::new(host);
+        for category in PatternCategory::ALL {
+            let category_dir = dir.join(category.as_str());
+            if !category_dir.is_dir() {
+                continue;
+            }
+            let mut files = std::fs::read_dir(&category_dir)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<io::Result<Vec<_>>>()?;
+            files.sort();
+            for file in files.iter().filter(|f| f.is_file()) {
+                let text = std::fs::read_to_string(file)?;
+                let parsed = pattern_file::parse(&text)
+                    .map_err(|e| invalid(format!("{}: {}", file.display(), e)))?;
+                if parsed.is_expired(SystemTime::now()) {
+                    continue;
+                }
+                for entry in &parsed.entries {
+                    let category = if entry.allow {
+                        PatternCategory::Allow
+                    } else {
+                        category
+                    };
+                    profile.insert_mode(category, entry.mode, &entry.pattern);
+                }
+            }
+        }
+        profile.shrink_to_fit();
+        Ok(profile)
+    }
+
+    /// Add `patterns` to `category` and return the profile.
+    pub fn with_patterns<I, S>(mut self, category: PatternCategory, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.extend(category, patterns);
+        self
+    }
+
+    /// Add one URL-prefix pattern to `category`.
+    pub fn insert(&mut self, category: PatternCategory, pattern: &str) {
+        self.insert_mode(category, MatchMode::Prefix, pattern);
+    }
+
+    /// Add one pattern to `category`, matched the way `mode` says.
+    pub fn insert_mode(&mut self, category: PatternCategory, mode: MatchMode, pattern: &str) {
+        self.lists[category.index()].insert(mode, pattern);
+    }
+
+    /// Add URL-prefix `patterns` to `category`.
+    pub fn extend<I, S>(&mut self, category: PatternCategory, patterns: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for pattern in patterns {
+            self.insert(category, pattern.as_ref());
+        }
+    }
+
+    /// The host this profile is registered under.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Number of patterns in `category`.
+    pub fn len(&self, category: PatternCategory) -> usize {
+        self.lists[category.index()].len()
+    }
+
+    /// `true` if no category holds a pattern.
+    pub fn is_empty(&self) -> bool {
+        self.lists.iter().all(|list| list.len() == 0)
+    }
+
+    /// Freeze every list into the packed representation and build the
+    /// substring matchers up front.
+    pub fn shrink_to_fit(&mut self) {
+        for list in &mut self.lists {
+            list.shrink_to_fit();
+        }
+    }
+
+    /// Check `url` against one list.
+    #[inline]
+    pub fn block(&self, category: PatternCategory, url: &str) -> bool {
+        self.lists[category.index()].matches(url)
+    }
+
+    /// Check `url` against the `allow` list.
+    #[inline]
+    pub fn is_allowed(&self, url: &str) -> bool {
+        self.block(PatternCategory::Allow, url)
+    }
+
+    /// Same as [`NetworkInterceptManager::intercept_resource`].
+    pub fn intercept_resource(&self, url: &str, resource_type: ResourceType) -> bool {
+        let category = match PatternCategory::for_resource(resource_type) {
+            Some(PatternCategory::Xhr) => PatternCategory::Xhr,
+            Some(category) if self.lists[category.index()].len() > 0 => category,
+            Some(_) => PatternCategory::Scripts,
+            None => return false,
+        };
+        !self.is_allowed(url) && self.block(category, url)
+    }
+
+    /// Same as [`NetworkInterceptManager::intercept_detection`].
+    pub fn intercept_detection(&self, url: &str, ignore_visuals: bool, is_xhr: bool) -> bool {
+        ResourceType::detection_types(ignore_visuals, is_xhr)
+            .iter()
+            .any(|&resource_type| self.intercept_resource(url, resource_type))
+    }
+}
+
+impl UrlMatcher for DomainProfile {
+    /// Visual requests only face the script list, like a
+    /// [`NetworkInterceptManager`].
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        let resource_type = request.resource_type.domain_list_type(false);
+        self.intercept_resource(request.url, resource_type)
+    }
+}
+
+/// What a host resolved to: a compiled-in variant or a registered profile.
+#[derive(Debug, Clone)]
+pub enum ResolvedProfile {
+    /// Compiled-in lists; [`NetworkInterceptManager::Unknown`] when nothing
+    /// matched.
+    Builtin(NetworkInterceptManager),
+    /// Registered at runtime.
+    Runtime(Arc<DomainProfile>),
+}
+
+impl Default for ResolvedProfile {
+    fn default() -> Self {
+        ResolvedProfile::Builtin(NetworkInterceptManager::Unknown)
+    }
+}
+
+impl ResolvedProfile {
+    /// Check `url` against the domain's `allow` list.
+    #[inline]
+    pub fn is_allowed(&self, url: &str) -> bool {
+        match self {
+            ResolvedProfile::Builtin(manager) => manager.is_allowed(url),
+            ResolvedProfile::Runtime(profile) => profile.is_allowed(url),
+        }
+    }
+
+    /// Check `url` against the domain's pattern set for `resource_type`.
+    #[inline]
+    pub fn intercept_resource(&self, url: &str, resource_type: ResourceType) -> bool {
+        match self {
+            ResolvedProfile::Builtin(manager) => manager.intercept_resource(url, resource_type),
+            ResolvedProfile::Runtime(profile) => profile.intercept_resource(url, resource_type),
+        }
+    }
+
+    /// Check `url` the way [`NetworkInterceptManager::intercept_detection`]
+    /// does.
+    #[inline]
+    pub fn intercept_detection(&self, url: &str, ignore_visuals: bool, is_xhr: bool) -> bool {
+        match self {
+            ResolvedProfile::Builtin(manager) => {
+                manager.intercept_detection(url, ignore_visuals, is_xhr)
+            }
+            ResolvedProfile::Runtime(profile) => {
+                profile.intercept_detection(url, ignore_visuals, is_xhr)
+            }
+        }
+    }
+}
+
+/// Registry of runtime domain profiles. Lookups are lock-free; registering
+/// publishes a new map the way
+/// [`DynamicBlockList`](crate::dynamic_blocklist::DynamicBlockList) publishes
+/// layers.
+#[derive(Debug, Default)]
+pub struct DomainProfiles {
+    profiles: ArcSwap<HashMap<String, Arc<DomainProfile>>>,
+}
+
+impl DomainProfiles {
+    /// Empty registry; every host resolves to its compiled-in variant.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `profile` under its host, returning the profile it replaced.
+    pub fn register(&self, profile: DomainProfile) -> Option<Arc<DomainProfile>> {
+        let profile = Arc::new(profile);
+        let mut previous = None;
+        self.profiles.rcu(|profiles| {
+            let mut profiles = HashMap::clone(profiles);
+            previous = profiles.insert(profile.host.clone(), Arc::clone(&profile));
+            profiles
+        });
+        previous
+    }
+
+    /// Remove the profile registered under `host`. Returns `false` if there
+    /// was none.
+    pub fn unregister(&self, host: &str) -> bool {
+        let host = DomainProfile::new(host).host;
+        let mut found = false;
+        self.profiles.rcu(|profiles| {
+            let mut profiles = HashMap::clone(profiles);
+            found = profiles.remove(&host).is_some();
+            profiles
+        });
+        found
+    }
+
+    /// The profile registered under exactly `host`.
+    pub fn get(&self, host: &str) -> Option<Arc<DomainProfile>> {
+        self.profiles.load().get(host).cloned()
+    }
+
+    /// Registered hosts, sorted.
+    pub fn hosts(&self) -> Vec<String> {
+        let mut hosts: Vec<_> = self.profiles.load().keys().cloned().collect();
+        hosts.sort_unstable();
+        hosts
+    }
+
+    /// Number of registered profiles.
+    pub fn len(&self) -> usize {
+        self.profiles.load().len()
+    }
+
+    /// `true` if nothing is registered.
+    pub fn is_empty(&self) -> bool {
+        self.profiles.load().is_empty()
+    }
+
+    /// Resolve `host` against registered profiles and compiled-in variants.
+    /// At each level of the walk a registered profile wins.
+    pub fn resolve(&self, host: &str) -> ResolvedProfile {
+        let profiles = self.profiles.load();
+        resolve_host(host, |candidate| match profiles.get(candidate) {
+            Some(profile) => Some(ResolvedProfile::Runtime(Arc::clone(profile))),
+            None => NetworkInterceptManager::exact(candidate).map(ResolvedProfile::Builtin),
+        })
+        .unwrap_or_default()
+    }
+
+    /// [`resolve`](Self::resolve) the host of an already-parsed URL.
+    pub fn resolve_url(&self, url: &url::Url) -> ResolvedProfile {
+        match url.domain() {
+            Some(domain) => self.resolve(domain),
+            None => ResolvedProfile::default(),
+        }
+    }
+
+    /// Register every subdirectory of `root` with
+    /// [`DomainProfile::from_dir`] — point it at a tree shaped like
+    /// `url_patterns/domains`. Returns the number of profiles loaded.
+    pub fn load_dir(&self, root: impl AsRef<Path>) -> io::Result<usize> {
+        let mut dirs = std::fs::read_dir(root)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        dirs.retain(|d| d.is_dir());
+        dirs.sort();
+        let mut loaded = Vec::with_capacity(dirs.len());
+        for dir in &dirs {
+            loaded.push(DomainProfile::from_dir(dir)?);
+        }
+        Ok(self.register_all(loaded))
+    }
+
+    /// Register the profiles in a config file. See the module docs for the
+    /// format. Returns the number of profiles loaded.
+    pub fn load_config(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let text = std::fs::read_to_string(path)?;
+        self.load_config_str(&text)
+    }
+
+    /// [`load_config`](Self::load_config) from a string.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] on a pattern before the
+    /// first `[host]`, an empty or repeated `[host]`, an unknown category, a
+    /// line without `=` or with nothing after it, or a value the
+    /// [`pattern_file`] grammar rejects; nothing is registered in that case.
+    pub fn load_config_str(&self, text: &str) -> io::Result<usize> {
+        // Each section's values per category, with their line numbers.
+        type Body<'a> = Vec<(usize, &'a str)>;
+        let mut sections: Vec<(DomainProfile, [Body; PatternCategory::ALL.len()])> = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line_no = n + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(host) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let profile = DomainProfile::new(host);
+                if profile.host.is_empty() {
+                    return Err(invalid(format!("line {}: empty [host]", line_no)));
+                }
+                if sections.iter().any(|(p, _)| p.host == profile.host) {
+                    return Err(invalid(format!(
+                        "line {}: duplicate [{}]",
+                        line_no, profile.host
+                    )));
+                }
+                sections.push((profile, Default::default()));
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                return Err(invalid(format!(
+                    "line {}: expected `category = pattern`",
+                    line_no
+                )));
+            };
+            let (name, value) = (name.trim(), value.trim());
+            let Some(category) = PatternCategory::from_name(name) else {
+                return Err(invalid(format!(
+                    "line {}: unknown category {:?}",
+                    line_no, name
+                )));
+            };
+            if value.is_empty() {
+                return Err(invalid(format!(
+                    "line {}: `{}` needs a pattern",
+                    line_no, name
+                )));
+            }
+            let Some((_, bodies)) = sections.last_mut() else {
+                return Err(invalid(format!(
+                    "line {}: pattern before any [host]",
+                    line_no
+                )));
+            };
+            bodies[category.index()].push((line_no, value));
+        }
+
+        let now = SystemTime::now();
+        let mut loaded = Vec::with_capacity(sections.len());
+        for (mut profile, bodies) in sections {
+            for (category, body) in PatternCategory::ALL.into_iter().zip(&bodies) {
+                let text = body
+                    .iter()
+                    .map(|(_, value)| *value)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let parsed = pattern_file::parse(&text)
+                    .map_err(|e| invalid(format!("line {}: {}", body[e.line - 1].0, e.message)))?;
+                if parsed.is_expired(now) {
+                    continue;
+                }
+                for entry in &parsed.entries {
+                    let category = if entry.allow {
+                        PatternCategory::Allow
+                    } else {
+                        category
+                    };
+                    profile.insert_mode(category, entry.mode, &entry.pattern);
+                }
+            }
+            profile.shrink_to_fit();
+            loaded.push(profile);
+        }
+        Ok(self.register_all(loaded))
+    }
+
+    /// Publish all of `loaded` as one new map.
+    fn register_all(&self, loaded: Vec<DomainProfile>) -> usize {
+        let count = loaded.len();
+        let loaded: Vec<_> = loaded.into_iter().map(Arc::new).collect();
+        self.profiles.rcu(|profiles| {
+            let mut profiles = HashMap::clone(profiles);
+            for profile in &loaded {
+                profiles.insert(profile.host.clone(), Arc::clone(profile));
+            }
+            profiles
+        });
+        count
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amazon_uk() -> DomainProfile {
+        DomainProfile::new("Amazon.co.uk.")
+            .with_patterns(
+                PatternCategory::Scripts,
+                ["https://www.amazon.co.uk/gp/ads/"],
+            )
+            .with_patterns(PatternCategory::Xhr, ["https://www.amazon.co.uk/rd/"])
+            .with_patterns(
+                PatternCategory::Allow,
+                ["https://www.amazon.co.uk/gp/ads/ok/"],
+            )
+    }
+
+    #[test]
+    fn profile_matches_like_builtin() {
+        let profile = amazon_uk();
+        assert_eq!(profile.host(), "amazon.co.uk");
+        let ad = "https://www.amazon.co.uk/gp/ads/x.js";
+        assert!(profile.intercept_resource(ad, ResourceType::Script));
+        assert!(profile.intercept_resource(ad, ResourceType::Other));
+        assert!(!profile.intercept_resource(ad, ResourceType::Document));
+        assert!(!profile.intercept_resource(ad, ResourceType::Xhr));
+        assert!(profile.intercept_detection(ad, false, false));
+        assert!(profile.intercept_detection("https://www.amazon.co.uk/rd/u", false, true));
+
+        let ok = "https://www.amazon.co.uk/gp/ads/ok/x.js";
+        assert!(profile.is_allowed(ok));
+        assert!(!profile.intercept_resource(ok, ResourceType::Script));
+        assert!(!profile.intercept_detection(ok, true, false));
+    }
+
+    #[test]
+    fn profile_falls_back_to_scripts() {
+        let profile = amazon_uk();
+        let ad = "https://www.amazon.co.uk/gp/ads/x.gif";
+        assert!(profile.intercept_resource(ad, ResourceType::Image));
+        assert!(profile.matches(&RequestContext::new(ad).with_resource_type(ResourceType::Image)));
+
+        let profile = profile.with_patterns(
+            PatternCategory::Images,
+            ["https://www.amazon.co.uk/images/ads/"],
+        );
+        assert!(!profile.intercept_resource(ad, ResourceType::Image));
+        assert!(profile.intercept_resource(
+            "https://www.amazon.co.uk/images/ads/x.gif",
+            ResourceType::Image
+        ));
+        // Visual lists are only consulted with `ignore_visuals`.
+        assert!(profile.matches(&RequestContext::new(ad).with_resource_type(ResourceType::Image)));
+    }
+
+    #[test]
+    fn detection_agrees_with_resource_types() {
+        use ResourceType::*;
+
+        let url = |category: PatternCategory| format!("https://example.com/{}/", category.as_str());
+        let mut profile = DomainProfile::new("example.com");
+        for category in PatternCategory::ALL {
+            profile.insert(category, &url(category));
+        }
+        for category in PatternCategory::ALL {
+            let url = url(category) + "x";
+            let resource = |t| profile.intercept_resource(&url, t);
+            for ignore_visuals in [false, true] {
+                assert_eq!(
+                    profile.intercept_detection(&url, ignore_visuals, true),
+                    resource(Xhr),
+                    "{url}"
+                );
+            }
+            assert_eq!(
+                profile.intercept_detection(&url, false, false),
+                resource(Script),
+                "{url}"
+            );
+            assert_eq!(
+                profile.intercept_detection(&url, true, false),
+                [Script, Stylesheet, Image, Font, Media]
+                    .into_iter()
+                    .any(resource),
+                "{url}"
+            );
+        }
+        // Beacon and iframe lists only apply to their own types.
+        assert!(!profile.intercept_detection("https://example.com/beacon/x", true, false));
+        assert!(!profile.intercept_detection("https://example.com/iframes/x", true, false));
+        assert!(profile.intercept_detection("https://example.com/images/x", true, false));
+    }
+
+    #[test]
+    fn profile_match_modes() {
+        let mut profile = DomainProfile::new("example.com");
+        profile.insert_mode(PatternCategory::Scripts, MatchMode::Host, "ads.net");
+        assert!(profile.block(PatternCategory::Scripts, "https://cdn.ads.net/a.js"));
+        assert!(!profile.block(PatternCategory::Scripts, "https://example.com/ads.net"));
+
+        profile.insert_mode(PatternCategory::Scripts, MatchMode::Substring, "/pixel.");
+        assert!(profile.block(PatternCategory::Scripts, "https://x.com/a/pixel.gif"));
+        // Inserting again invalidates the built matcher.
+        profile.insert_mode(PatternCategory::Scripts, MatchMode::Substring, "/beacon.");
+        assert!(profile.block(PatternCategory::Scripts, "https://x.com/a/beacon.js"));
+        assert_eq!(profile.len(PatternCategory::Scripts), 3);
+    }
+
+    #[test]
+    fn registry_resolves_with_builtin_walk() {
+        let profiles = DomainProfiles::new();
+        assert!(matches!(
+            profiles.resolve("www.amazon.co.uk"),
+            ResolvedProfile::Builtin(NetworkInterceptManager::Unknown)
+        ));
+
+        assert!(profiles.register(amazon_uk()).is_none());
+        assert!(matches!(
+            profiles.resolve("WWW.amazon.co.uk"),
+            ResolvedProfile::Runtime(p) if p.host() == "amazon.co.uk"
+        ));
+        // Compiled-in variants still resolve.
+        assert!(matches!(
+            profiles.resolve("www.amazon.com"),
+            ResolvedProfile::Builtin(NetworkInterceptManager::Amazon)
+        ));
+        // A more specific compiled-in key wins over a registered parent.
+        profiles.register(DomainProfile::new("archive.org"));
+        assert!(matches!(
+            profiles.resolve("web.archive.org"),
+            ResolvedProfile::Builtin(NetworkInterceptManager::WebArchive)
+        ));
+        // A registered profile wins at the same level.
+        profiles.register(DomainProfile::new("amazon.com"));
+        assert!(matches!(
+            profiles.resolve("www.amazon.com"),
+            ResolvedProfile::Runtime(_)
+        ));
+
+        assert!(profiles.unregister("Amazon.com"));
+        assert!(!profiles.unregister("amazon.com"));
+        assert_eq!(profiles.hosts(), ["amazon.co.uk", "archive.org"]);
+    }
+
+    #[test]
+    fn loads_config() {
+        let profiles = DomainProfiles::new();
+        let loaded = profiles
+            .load_config_str(
+                "# profiles\n\
+                 [example.com]\n\
+                 scripts = https://example.com/ads/\n\
+                 xhr = https://example.com/track?a=b\n\
+                 scripts = @host\n\
+                 scripts = *.ads.net\n\
+                 scripts = !allow ok.ads.net\n\
+                 \n\
+                 [example.org]\n\
+                 allow = https://example.org/ok/\n",
+            )
+            .unwrap();
+        assert_eq!(loaded, 2);
+        let p = profiles.get("example.com").unwrap();
+        assert_eq!(p.len(PatternCategory::Scripts), 2);
+        assert!(p.block(PatternCategory::Xhr, "https://example.com/track?a=b&c"));
+        // `@host` only applies to the scripts lines after it.
+        assert!(p.block(PatternCategory::Scripts, "https://cdn.ads.net/a.js"));
+        assert!(p.is_allowed("https://ok.ads.net/a.js"));
+        assert!(!p.block(PatternCategory::Xhr, "https://cdn.ads.net/"));
+        assert!(profiles
+            .get("example.org")
+            .unwrap()
+            .is_allowed("https://example.org/ok/1"));
+
+        for (bad, needle) in [
+            ("scripts = https://a/", "line 1: pattern before"),
+            ("[a.com]\nbogus = https://a/", "line 2: unknown category"),
+            ("[a.com]\nhttps://a/", "line 2: expected"),
+            ("[]\nscripts = https://a/", "line 1: empty"),
+            ("[a.com]\nscripts =", "line 2: `scripts` needs a pattern"),
+            ("[a.com]\n[b.com]\n[A.com.]", "line 3: duplicate [a.com]"),
+            (
+                "[a.com]\nxhr = https://a/\nscripts = @regex",
+                "line 3: unknown directive",
+            ),
+            (
+                "[a.com]\nscripts = @host\nscripts = a/b",
+                "line 3: invalid host",
+            ),
+        ] {
+            let profiles = DomainProfiles::new();
+            let err = profiles.load_config_str(bad).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{bad}");
+            assert!(err.to_string().contains(needle), "{bad:?}: {err}");
+            assert!(profiles.is_empty());
+        }
+    }
+
+    #[test]
+    fn loads_pattern_directories() {
+        let profiles = DomainProfiles::new();
+        let loaded = profiles
+            .load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/url_patterns/domains"))
+            .unwrap();
+        assert_eq!(loaded, profiles.len());
+        let amazon = profiles.get("amazon.com").unwrap();
+        assert!(amazon.len(PatternCategory::Scripts) > 0);
+        assert!(amazon.len(PatternCategory::Images) > 0);
+        // Same answers as the compiled-in lists.
+        let builtin = NetworkInterceptManager::Amazon;
+        for url in [
+            "https://m.media-amazon.com/images/I/abc.jpg",
+            "https://images-na.ssl-images-amazon.com/images/G/01/AUIClients/x.js",
+        ] {
+            for kind in [ResourceType::Script, ResourceType::Image] {
+                assert_eq!(
+                    amazon.intercept_resource(url, kind),
+                    builtin.intercept_resource(url, kind),
+                    "{url} {kind}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pattern_directories_use_the_file_grammar() {
+        let dir = std::env::temp_dir().join(format!(
+            "spider_network_blocker_profile_{}/example.com",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(
+            dir.join("scripts/a.txt"),
+            "https://example.com/ads/\n\
+             @host\n\
+             tracker.net\n\
+             !allow ok.tracker.net\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("scripts/b.txt"),
+            "expires: 2001-01-01\nhttps://example.com/old/\n",
+        )
+        .unwrap();
+        let profile = DomainProfile::from_dir(&dir).unwrap();
+        assert!(profile.block(PatternCategory::Scripts, "https://a.tracker.net/t.js"));
+        assert!(profile.is_allowed("https://ok.tracker.net/t.js"));
+        assert!(!profile.block(PatternCategory::Scripts, "https://example.com/old/x"));
+
+        std::fs::write(dir.join("scripts/b.txt"), "@regex\n").unwrap();
+        let err = DomainProfile::from_dir(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string()
+                .ends_with("b.txt: line 1: unknown directive \"@regex\""),
+            "{err}"
+        );
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+}
@@ -1,32 +1,213 @@
-use crate::trie::Trie;
+use crate::trie::{PrefixMatch, PrefixMatcher, Trie};
 use arc_swap::ArcSwap;
-use std::sync::Arc;
+use hits::HitCounter;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+mod hits;
+mod notify;
+mod persist;
+
+pub use notify::{BlockListChange, SubscriptionId};
+
+/// Snapshots kept for [`DynamicBlockList::rollback`] unless configured via
+/// [`DynamicBlockList::with_history_limit`].
+pub const DEFAULT_HISTORY_LIMIT: usize = 16;
+
+/// When [`DynamicBlockList`] compacts on its own.
+///
+/// Every lookup probes each dynamic layer in turn, so lookup cost grows
+/// linearly with [`LayerStats::probes_per_lookup`]. Compacting merges all
+/// permanent layers into one, paying a rebuild proportional to
+/// [`LayerStats::patterns`] once. The default policy never compacts
+/// automatically — call [`DynamicBlockList::compact`] or run a
+/// [`Compactor`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionPolicy {
+    /// Compact once the list holds more than this many block layers.
+    pub max_layers: Option<usize>,
+    /// Compact once more than this many patterns sit in permanent layers
+    /// other than the first (merged) one of their source.
+    pub max_uncompacted_patterns: Option<usize>,
+}
+
+impl CompactionPolicy {
+    /// Never compact automatically.
+    pub const MANUAL: Self = Self {
+        max_layers: None,
+        max_uncompacted_patterns: None,
+    };
+
+    /// Whether a list in state `stats` should be compacted now. Only true
+    /// when compacting would actually merge something.
+    pub fn should_compact(&self, stats: &LayerStats) -> bool {
+        stats.permanent_layers > stats.sources
+            && (self.max_layers.is_some_and(|n| stats.layers > n)
+                || self
+                    .max_uncompacted_patterns
+                    .is_some_and(|n| stats.uncompacted_patterns > n))
+    }
+}
+
+/// Shape of a [`DynamicBlockList`]'s dynamic layers, for inspecting the
+/// lookup cost and the [`CompactionPolicy`] inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LayerStats {
+    /// Block layers, permanent and TTL.
+    pub layers: usize,
+    /// Block layers without a TTL — the ones `compact` merges.
+    pub permanent_layers: usize,
+    /// Distinct source tags among permanent layers (untagged counts as one).
+    /// `compact` merges down to one layer per source.
+    pub sources: usize,
+    /// Block layers with a TTL, expired or not.
+    pub ttl_layers: usize,
+    /// TTL layers already past their deadline, awaiting a sweep.
+    pub expired_layers: usize,
+    /// Allow-override layers.
+    pub allow_layers: usize,
+    /// Patterns across all block layers.
+    pub patterns: usize,
+    /// Patterns in permanent layers after the first one of their source.
+    pub uncompacted_patterns: usize,
+}
+
+impl LayerStats {
+    /// Worst-case trie probes for one lookup, excluding the base.
+    pub fn probes_per_lookup(&self) -> usize {
+        self.layers + self.allow_layers
+    }
+}
+
+/// A match reported by [`DynamicBlockList::lookup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMatch<'a> {
+    /// The matched pattern, borrowed from the lookup input.
+    pub pattern: &'a str,
+    /// Source label of the matching layer; `None` for untagged layers and
+    /// the base trie.
+    pub source: Option<Arc<str>>,
+    /// `true` if the base trie matched rather than a dynamic layer.
+    pub base: bool,
+}
+
+/// Hit count of one dynamic layer, from [`DynamicBlockList::layer_hits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerHits {
+    /// Source label the layer was added with.
+    pub source: Option<Arc<str>>,
+    /// Patterns stored in the layer.
+    pub patterns: usize,
+    /// Lookups this layer decided.
+    pub hits: u64,
+    /// `true` for allow-override layers.
+    pub allow: bool,
+    /// `true` for TTL layers.
+    pub ttl: bool,
+}
+
+/// One immutable layer: a frozen trie plus an optional expiry.
+struct Layer {
+    trie: Trie,
+    /// The layer stops matching at this instant. `None` never expires.
+    expires_at: Option<Instant>,
+    /// Feed the patterns came from, reported on match.
+    source: Option<Arc<str>>,
+    /// Lookups this layer decided.
+    hits: HitCounter,
+}
+
+impl Layer {
+    /// Freeze here so every layer reader hits the packed fast path and we
+    /// never hold a Vec-form trie behind an `Arc`.
+    fn new(mut trie: Trie, expires_at: Option<Instant>, source: Option<Arc<str>>) -> Arc<Self> {
+        trie.shrink_to_fit();
+        Arc::new(Self {
+            trie,
+            expires_at,
+            source,
+            hits: HitCounter::default(),
+        })
+    }
+
+    /// Replacement for this layer holding `trie`, carrying over its expiry,
+    /// source and hit count.
+    fn rebuild(&self, trie: Trie) -> Arc<Self> {
+        let layer = Self::new(trie, self.expires_at, self.source.clone());
+        layer.hits.set(self.hits());
+        layer
+    }
+
+    fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    /// Whether the layer is still active. The clock is read at most once
+    /// per lookup, and only when a TTL layer is actually consulted.
+    #[inline]
+    fn is_live(&self, now: &mut Option<Instant>) -> bool {
+        match self.expires_at {
+            None => true,
+            Some(at) => *now.get_or_insert_with(Instant::now) < at,
+        }
+    }
+}
 
 /// Immutable snapshot of trie layers. Each `extend` adds a layer
 /// instead of cloning and rebuilding.
 #[derive(Default)]
 struct Layers {
-    tries: Vec<Arc<Trie>>,
+    /// Assigned by `DynamicBlockList::publish`; strictly increasing.
+    version: u64,
+    tries: Vec<Arc<Layer>>,
+    /// Allow overrides, checked before the base and the block layers.
+    allow: Vec<Arc<Layer>>,
 }
 
 impl Layers {
-    fn with_trie(mut trie: Trie) -> Self {
-        // Freeze here so every layer reader hits the packed fast path and
-        // we never hold a Vec-form trie behind an `Arc`.
-        trie.shrink_to_fit();
+    fn with_trie(trie: Trie) -> Self {
+        Self {
+            version: 0,
+            tries: vec![Layer::new(trie, None, None)],
+            allow: Vec::new(),
+        }
+    }
+
+    /// Unversioned copy with the given block layers and these allow layers.
+    fn with_tries(&self, tries: Vec<Arc<Layer>>) -> Self {
         Self {
-            tries: vec![Arc::new(trie)],
+            version: 0,
+            tries,
+            allow: self.allow.clone(),
+        }
+    }
+
+    /// Unversioned copy with these block layers and the given allow layers.
+    fn with_allow(&self, allow: Vec<Arc<Layer>>) -> Self {
+        Self {
+            version: 0,
+            tries: self.tries.clone(),
+            allow,
         }
     }
 
     #[inline]
     fn contains_prefix(&self, text: &str) -> bool {
-        for trie in &self.tries {
-            if trie.contains_prefix(text) {
-                return true;
-            }
-        }
-        false
+        self.find(text).is_some()
+    }
+
+    /// First live block layer matching `text`, counting the hit.
+    #[inline]
+    fn find<'a>(&self, text: &'a str) -> Option<(PrefixMatch<'a>, &Layer)> {
+        find_live(&self.tries, text)
+    }
+
+    #[inline]
+    fn is_allowed(&self, text: &str) -> bool {
+        find_live(&self.allow, text).is_some()
     }
 
     fn len(&self) -> usize {
@@ -34,29 +215,134 @@ impl Layers {
     }
 }
 
+/// First live layer in `layers` matching `text`, counting the hit.
+#[inline]
+fn find_live<'a, 'l>(
+    layers: &'l [Arc<Layer>],
+    text: &'a str,
+) -> Option<(PrefixMatch<'a>, &'l Layer)> {
+    let mut now = None;
+    for layer in layers {
+        // Match first, check expiry second: misses never read the clock.
+        if let Some(m) = layer.trie.find_prefix(text) {
+            if layer.is_live(&mut now) {
+                layer.hits.add();
+                return Some((m, layer));
+            }
+        }
+    }
+    None
+}
+
+/// Copy of `layers` with `pattern` dropped from every layer that stores it
+/// exactly. Layers left empty are dropped. `None` if no layer had it.
+fn without_pattern(layers: &[Arc<Layer>], pattern: &str) -> Option<Vec<Arc<Layer>>> {
+    if !layers.iter().any(|layer| layer.trie.get(pattern).is_some()) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(layers.len());
+    for layer in layers {
+        if layer.trie.get(pattern).is_none() {
+            // Untouched layers are shared, not copied.
+            out.push(Arc::clone(layer));
+            continue;
+        }
+        let mut rebuilt = Trie::new();
+        layer.trie.for_each_word(|bytes| {
+            if bytes != pattern.as_bytes() {
+                if let Ok(word) = std::str::from_utf8(bytes) {
+                    rebuilt.insert(word);
+                }
+            }
+        });
+        if !rebuilt.is_empty() {
+            out.push(layer.rebuild(rebuilt));
+        }
+    }
+    Some(out)
+}
+
 /// A lock-free, dynamically updatable block list backed by layered [`Trie`]s.
 ///
-/// Can optionally wrap an existing static `&Trie` (e.g. `URL_IGNORE_TRIE`)
-/// as a base that is always checked first. Runtime patterns are added as
-/// layers on top — `extend` never clones existing data.
+/// Can optionally wrap one or more immutable bases — a `StaticTrie` such
+/// as `URL_IGNORE_TRIE`, an `Arc<Trie>` loaded at runtime, or any other
+/// [`PrefixMatcher`] — that are always checked first. Runtime patterns are added as
+/// layers on top — `extend` never clones existing data. Allow overrides
+/// (see [`DynamicBlockList::allow`]) are checked before both.
 ///
 /// Reads are wait-free (atomic pointer load). Writers build off the hot path
-/// and atomically swap in the new layer set.
+/// and atomically swap in the new layer set. Every change publishes a new
+/// numbered snapshot; a bounded history of them backs
+/// [`rollback`](DynamicBlockList::rollback).
 pub struct DynamicBlockList {
-    base: Option<&'static Trie>,
+    /// Immutable bases, checked in insertion order before the layers.
+    bases: Vec<Base>,
     layers: ArcSwap<Layers>,
+    /// Recently published snapshots, oldest first, for `rollback`.
+    history: ArcSwap<VecDeque<Arc<Layers>>>,
+    history_limit: usize,
+    /// Source of snapshot versions.
+    next_version: AtomicU64,
+    compaction: CompactionPolicy,
+    subscribers: ArcSwap<Vec<(u64, notify::Subscriber)>>,
+    next_subscriber: AtomicU64,
+    /// Lookups decided by the base trie.
+    base_hits: HitCounter,
+}
+
+/// An immutable base of a [`DynamicBlockList`].
+type Base = Box<dyn PrefixMatcher + Send + Sync>;
+
+/// A published state of a [`DynamicBlockList`]'s dynamic layers.
+///
+/// Cheap to hold — it shares layers with the list via `Arc`. Pass its
+/// [`version`](Snapshot::version) to [`DynamicBlockList::rollback`] to
+/// restore it.
+#[derive(Clone)]
+pub struct Snapshot {
+    layers: Arc<Layers>,
+}
+
+impl Snapshot {
+    /// Version number of this snapshot.
+    pub fn version(&self) -> u64 {
+        self.layers.version
+    }
+
+    /// Number of dynamic block layers in this snapshot.
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Check `url` against this snapshot's allow overrides and dynamic
+    /// layers only — the list's base trie is not consulted.
+    pub fn is_blocked(&self, url: &str) -> bool {
+        !self.layers.is_allowed(url) && self.layers.contains_prefix(url)
+    }
 }
 
 impl DynamicBlockList {
-    /// Create an empty dynamic block list with no base trie.
-    pub fn new() -> Self {
+    fn from_parts(bases: Vec<Base>, layers: Layers) -> Self {
+        let layers = Arc::new(layers);
         Self {
-            base: None,
-            layers: ArcSwap::from_pointee(Layers::default()),
+            bases,
+            history: ArcSwap::from_pointee(VecDeque::from([Arc::clone(&layers)])),
+            layers: ArcSwap::new(layers),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            next_version: AtomicU64::new(1),
+            compaction: CompactionPolicy::MANUAL,
+            subscribers: ArcSwap::from_pointee(Vec::new()),
+            next_subscriber: AtomicU64::new(0),
+            base_hits: HitCounter::default(),
         }
     }
 
-    /// Wrap an existing static trie as the base layer.
+    /// Create an empty dynamic block list with no base trie.
+    pub fn new() -> Self {
+        Self::from_parts(Vec::new(), Layers::default())
+    }
+
+    /// Wrap an existing trie as the base layer.
     ///
     /// The base is always checked first and is never affected by `seed`,
     /// `swap`, `extend`, or `compact` — those only operate on dynamic layers.
@@ -65,18 +351,37 @@ impl DynamicBlockList {
     /// use spider_network_blocker::scripts::URL_IGNORE_TRIE;
     /// use spider_network_blocker::dynamic_blocklist::DynamicBlockList;
     ///
-    /// let blocklist = DynamicBlockList::with_base(&URL_IGNORE_TRIE);
+    /// let blocklist = DynamicBlockList::with_base(URL_IGNORE_TRIE);
     /// blocklist.extend(["https://my-custom-tracker.com/"]);
     ///
     /// // Checks the static trie first, then dynamic layers
     /// blocklist.is_blocked("https://www.google-analytics.com/analytics.js"); // true (from base)
     /// blocklist.is_blocked("https://my-custom-tracker.com/pixel");           // true (from extend)
     /// ```
-    pub fn with_base(base: &'static Trie) -> Self {
-        Self {
-            base: Some(base),
-            layers: ArcSwap::from_pointee(Layers::default()),
-        }
+    ///
+    /// `base` can be a `&'static Trie`, an owned or `Arc`'d [`Trie`] (e.g.
+    /// one loaded from a config file at startup), or any [`PrefixMatcher`].
+    pub fn with_base(base: impl PrefixMatcher + Send + Sync + 'static) -> Self {
+        Self::from_parts(vec![Box::new(base)], Layers::default())
+    }
+
+    /// Add another base, checked after the ones already present.
+    ///
+    /// ```rust,ignore
+    /// use spider_network_blocker::scripts::{URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_TRIE};
+    /// use spider_network_blocker::dynamic_blocklist::DynamicBlockList;
+    ///
+    /// let blocklist =
+    ///     DynamicBlockList::with_base(URL_IGNORE_TRIE).and_base(URL_IGNORE_EMBEDED_TRIE);
+    /// ```
+    pub fn and_base(mut self, base: impl PrefixMatcher + Send + Sync + 'static) -> Self {
+        self.bases.push(Box::new(base));
+        self
+    }
+
+    /// Number of immutable bases.
+    pub fn base_count(&self) -> usize {
+        self.bases.len()
     }
 
     /// Create a dynamic block list pre-seeded with `patterns` and no base trie.
@@ -85,38 +390,155 @@ impl DynamicBlockList {
         for p in patterns {
             trie.insert(p);
         }
-        Self {
-            base: None,
-            layers: ArcSwap::from_pointee(Layers::with_trie(trie)),
+        Self::from_parts(Vec::new(), Layers::with_trie(trie))
+    }
+
+    /// Keep at most `limit` snapshots (the current one included) available
+    /// to [`rollback`](Self::rollback). Defaults to [`DEFAULT_HISTORY_LIMIT`].
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        let mut history = VecDeque::clone(&self.history.load());
+        trim_history(&mut history, limit);
+        self.history.store(Arc::new(history));
+        self
+    }
+
+    /// Compact automatically after `extend` whenever `policy` says so. The
+    /// merge runs on the writing thread; readers are never blocked.
+    pub fn with_compaction_policy(mut self, policy: CompactionPolicy) -> Self {
+        self.compaction = policy;
+        self
+    }
+
+    /// The configured automatic compaction policy.
+    pub fn compaction_policy(&self) -> CompactionPolicy {
+        self.compaction
+    }
+
+    /// Current layer shape — the inputs to the compaction policy.
+    pub fn stats(&self) -> LayerStats {
+        let layers = self.layers.load();
+        let now = Instant::now();
+        let mut stats = LayerStats {
+            layers: layers.len(),
+            allow_layers: layers.allow.len(),
+            ..LayerStats::default()
+        };
+        let mut sources: Vec<Option<&str>> = Vec::new();
+        for layer in &layers.tries {
+            let patterns = layer.trie.len();
+            stats.patterns += patterns;
+            match layer.expires_at {
+                None => {
+                    let source = layer.source.as_deref();
+                    if sources.contains(&source) {
+                        stats.uncompacted_patterns += patterns;
+                    } else {
+                        sources.push(source);
+                    }
+                    stats.permanent_layers += 1;
+                }
+                Some(at) => {
+                    stats.ttl_layers += 1;
+                    if at <= now {
+                        stats.expired_layers += 1;
+                    }
+                }
+            }
         }
+        stats.sources = sources.len();
+        stats
     }
 
     /// Lock-free check — returns `true` if `url` matches any prefix in the
-    /// base trie or any dynamic layer.
+    /// base trie or any dynamic layer, and no allow override.
     #[inline]
     pub fn is_blocked(&self, url: &str) -> bool {
-        if let Some(base) = self.base {
-            if base.contains_prefix(url) {
-                return true;
-            }
+        let layers = self.layers.load();
+        if layers.is_allowed(url) {
+            return false;
+        }
+        if self.bases.iter().any(|base| base.contains_prefix(url)) {
+            self.base_hits.add();
+            return true;
         }
-        self.layers.load().contains_prefix(url)
+        layers.contains_prefix(url)
+    }
+
+    /// Lock-free lookup that reports the pattern responsible for a block.
+    ///
+    /// Checks allow overrides, then the base trie, then each dynamic layer
+    /// in insertion order, and returns the first match — `None` if `url` is
+    /// not blocked.
+    #[inline]
+    pub fn blocked_by<'a>(&self, url: &'a str) -> Option<PrefixMatch<'a>> {
+        self.lookup(url).map(|m| PrefixMatch {
+            pattern: m.pattern,
+            len: m.pattern.len(),
+        })
+    }
+
+    /// Like [`blocked_by`](Self::blocked_by), also reporting which feed
+    /// the matching pattern came from.
+    #[inline]
+    pub fn lookup<'a>(&self, url: &'a str) -> Option<BlockMatch<'a>> {
+        let layers = self.layers.load();
+        if layers.is_allowed(url) {
+            return None;
+        }
+        if let Some(m) = self.bases.iter().find_map(|base| base.find_prefix(url)) {
+            self.base_hits.add();
+            return Some(BlockMatch {
+                pattern: m.pattern,
+                source: None,
+                base: true,
+            });
+        }
+        layers.find(url).map(|(m, layer)| BlockMatch {
+            pattern: m.pattern,
+            source: layer.source.clone(),
+            base: false,
+        })
+    }
+
+    /// Lookups decided by the base trie so far.
+    pub fn base_hits(&self) -> u64 {
+        self.base_hits.get()
+    }
+
+    /// Hit counts of the current dynamic layers — block layers first, then
+    /// allow overrides. Counts carry over through `compact` and `remove`.
+    pub fn layer_hits(&self) -> Vec<LayerHits> {
+        let layers = self.layers.load();
+        let block = layers.tries.iter().map(|l| (l, false));
+        let allow = layers.allow.iter().map(|l| (l, true));
+        block
+            .chain(allow)
+            .map(|(layer, allow)| LayerHits {
+                source: layer.source.clone(),
+                patterns: layer.trie.len(),
+                hits: layer.hits(),
+                allow,
+                ttl: layer.expires_at.is_some(),
+            })
+            .collect()
     }
 
     /// Atomically replace all dynamic layers with a single `new_trie`.
-    /// The base trie (if any) is unaffected.
+    /// The bases (if any) are unaffected.
     pub fn swap(&self, new_trie: Trie) {
-        self.layers.store(Arc::new(Layers::with_trie(new_trie)));
+        let layer = Layer::new(new_trie, None, None);
+        self.publish(|current| Some(current.with_tries(vec![Arc::clone(&layer)])));
     }
 
     /// Replace all dynamic layers with a single trie built from `patterns`.
-    /// The base trie (if any) is unaffected.
+    /// The bases (if any) are unaffected.
     pub fn seed<'a>(&self, patterns: impl IntoIterator<Item = &'a str>) {
         let mut trie = Trie::new();
         for p in patterns {
             trie.insert(p);
         }
-        self.layers.store(Arc::new(Layers::with_trie(trie)));
+        self.swap(trie);
     }
 
     /// Extend the block list with additional patterns — lock-free, no cloning.
@@ -124,36 +546,325 @@ impl DynamicBlockList {
     /// Builds a small trie from only the new patterns and appends it as a new
     /// layer. Existing layers are shared via `Arc` — zero copying.
     pub fn extend<'a>(&self, patterns: impl IntoIterator<Item = &'a str>) {
+        self.push_layer(patterns, None, None);
+    }
+
+    /// [`extend`](Self::extend) with a source label, reported by
+    /// [`lookup`](Self::lookup) and [`layer_hits`](Self::layer_hits).
+    pub fn extend_tagged<'a>(&self, source: &str, patterns: impl IntoIterator<Item = &'a str>) {
+        self.push_layer(patterns, None, Some(Arc::from(source)));
+    }
+
+    /// Replace every layer tagged `source` with a single layer built from
+    /// `patterns`. Layers from other sources are kept, so each feed can be
+    /// re-seeded independently.
+    pub fn seed_tagged<'a>(&self, source: &str, patterns: impl IntoIterator<Item = &'a str>) {
         let mut trie = Trie::new();
         for p in patterns {
             trie.insert(p);
         }
-        let current = self.layers.load();
-        let mut new_layers = Layers {
-            tries: current.tries.clone(), // clones Arc pointers, not trie data
-        };
-        new_layers.tries.push(Arc::new(trie));
-        self.layers.store(Arc::new(new_layers));
+        let layer = Layer::new(trie, None, Some(Arc::from(source)));
+        self.publish(|current| {
+            let mut tries: Vec<_> = current
+                .tries
+                .iter()
+                .filter(|l| l.source.as_deref() != Some(source))
+                .cloned()
+                .collect();
+            tries.push(Arc::clone(&layer));
+            Some(current.with_tries(tries))
+        });
     }
 
-    /// Merge all dynamic layers into a single trie.
-    /// The base trie (if any) is unaffected.
+    /// Extend the block list with patterns that stop matching after `ttl`.
+    ///
+    /// Expiry is checked lazily on read — the hot path stays wait-free and
+    /// only reads the clock when a TTL layer matches. Expired layers are
+    /// dropped on the next `compact` or [`purge_expired`](Self::purge_expired).
+    pub fn extend_with_ttl<'a>(&self, patterns: impl IntoIterator<Item = &'a str>, ttl: Duration) {
+        // An overflowing deadline is as good as no deadline.
+        self.push_layer(patterns, Instant::now().checked_add(ttl), None);
+    }
+
+    fn push_layer<'a>(
+        &self,
+        patterns: impl IntoIterator<Item = &'a str>,
+        expires_at: Option<Instant>,
+        source: Option<Arc<str>>,
+    ) {
+        let mut trie = Trie::new();
+        for p in patterns {
+            trie.insert(p);
+        }
+        let layer = Layer::new(trie, expires_at, source);
+        self.publish(|current| {
+            let mut tries = current.tries.clone(); // clones Arc pointers, not trie data
+            tries.push(Arc::clone(&layer));
+            Some(current.with_tries(tries))
+        });
+        if self.compaction != CompactionPolicy::MANUAL
+            && self.compaction.should_compact(&self.stats())
+        {
+            self.compact();
+        }
+    }
+
+    /// Retract a single block pattern from the dynamic layers — lock-free.
+    ///
+    /// Only layers that store `pattern` exactly are rebuilt; every other
+    /// layer is shared via `Arc`. Returns `true` if the pattern was found.
+    /// The bases (if any) are unaffected — use [`allow`](Self::allow) to
+    /// override it.
+    pub fn remove(&self, pattern: &str) -> bool {
+        self.publish(|current| {
+            without_pattern(&current.tries, pattern).map(|tries| current.with_tries(tries))
+        })
+        .is_some()
+    }
+
+    /// Add allow overrides — lock-free. Any URL starting with one of
+    /// `patterns` is never blocked, whether it matches the base trie or a
+    /// dynamic layer. Survives `seed`, `swap` and `compact`.
+    pub fn allow<'a>(&self, patterns: impl IntoIterator<Item = &'a str>) {
+        let mut trie = Trie::new();
+        for p in patterns {
+            trie.insert(p);
+        }
+        let layer = Layer::new(trie, None, None);
+        self.publish(|current| {
+            let mut allow = current.allow.clone();
+            allow.push(Arc::clone(&layer));
+            Some(current.with_allow(allow))
+        });
+    }
+
+    /// Retract a single allow override. Returns `true` if it was found.
+    pub fn remove_allow(&self, pattern: &str) -> bool {
+        self.publish(|current| {
+            without_pattern(&current.allow, pattern).map(|allow| current.with_allow(allow))
+        })
+        .is_some()
+    }
+
+    /// Merge all dynamic layers into a single trie per source.
+    /// The bases (if any) are unaffected.
+    ///
+    /// Expired TTL layers are dropped; live TTL layers are kept as-is so
+    /// they still expire on schedule. Hit counts of merged layers are summed.
     pub fn compact(&self) {
-        let current = self.layers.load();
-        if current.len() <= 1 {
-            return;
+        self.publish(|current| {
+            let now = Instant::now();
+            // Permanent layers grouped by source, in first-seen order.
+            let mut groups: Vec<(Option<&str>, Vec<&Arc<Layer>>)> = Vec::new();
+            let mut live_ttl = Vec::new();
+            let mut expired = false;
+            for layer in &current.tries {
+                match layer.expires_at {
+                    None => {
+                        let source = layer.source.as_deref();
+                        match groups.iter_mut().find(|(s, _)| *s == source) {
+                            Some((_, group)) => group.push(layer),
+                            None => groups.push((source, vec![layer])),
+                        }
+                    }
+                    Some(at) if at > now => live_ttl.push(Arc::clone(layer)),
+                    Some(_) => expired = true,
+                }
+            }
+            if !expired && groups.iter().all(|(_, group)| group.len() <= 1) {
+                return None;
+            }
+            let mut tries = Vec::with_capacity(groups.len() + live_ttl.len());
+            for (_, group) in groups {
+                if let [single] = group[..] {
+                    tries.push(Arc::clone(single));
+                    continue;
+                }
+                let mut merged = Trie::new();
+                for layer in &group {
+                    collect_into(&layer.trie, &mut merged);
+                }
+                if merged.is_empty() {
+                    continue;
+                }
+                let layer = group[0].rebuild(merged);
+                let hits = group.iter().map(|l| l.hits()).sum();
+                layer.hits.set(hits);
+                tries.push(layer);
+            }
+            tries.extend(live_ttl);
+            Some(current.with_tries(tries))
+        });
+    }
+
+    /// Drop expired TTL layers without merging anything. Returns the number
+    /// of layers removed.
+    pub fn purge_expired(&self) -> usize {
+        let mut purged = 0;
+        self.publish(|current| {
+            let now = Instant::now();
+            let tries: Vec<_> = current
+                .tries
+                .iter()
+                .filter(|layer| layer.expires_at.is_none_or(|at| at > now))
+                .cloned()
+                .collect();
+            purged = current.len() - tries.len();
+            (purged > 0).then(|| current.with_tries(tries))
+        });
+        purged
+    }
+
+    /// Version of the current snapshot. Bumped by every change.
+    pub fn version(&self) -> u64 {
+        self.layers.load().version
+    }
+
+    /// Capture the current dynamic layers.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            layers: self.layers.load_full(),
         }
-        let mut merged = Trie::new();
-        for trie in &current.tries {
-            collect_into(trie, &mut merged);
+    }
+
+    /// Versions still available to [`rollback`](Self::rollback), oldest
+    /// first.
+    pub fn versions(&self) -> Vec<u64> {
+        self.history.load().iter().map(|l| l.version).collect()
+    }
+
+    /// Restore the dynamic layers (and allow overrides) of a retained
+    /// snapshot — lock-free, visible to all readers at once.
+    ///
+    /// The restored state is published as a new version, so versions keep
+    /// increasing and the rollback itself can be undone. Returns `false` if
+    /// `version` is no longer in the history.
+    pub fn rollback(&self, version: u64) -> bool {
+        let target = match self.history.load().iter().find(|l| l.version == version) {
+            Some(layers) => Arc::clone(layers),
+            None => return false,
+        };
+        self.publish(|_| {
+            Some(Layers {
+                version: 0,
+                tries: target.tries.clone(),
+                allow: target.allow.clone(),
+            })
+        });
+        true
+    }
+
+    /// Single write path: derive the next snapshot from the current one,
+    /// stamp it with a fresh version and swap it in. `update` returns `None`
+    /// to leave the list unchanged; it may run more than once if writers
+    /// race. Returns the published snapshot.
+    fn publish<F>(&self, mut update: F) -> Option<Arc<Layers>>
+    where
+        F: FnMut(&Layers) -> Option<Layers>,
+    {
+        let mut published = None;
+        let mut previous = None;
+        self.layers.rcu(|current| match update(current) {
+            Some(mut next) => {
+                next.version = self.next_version.fetch_add(1, Ordering::Relaxed);
+                let next = Arc::new(next);
+                published = Some(Arc::clone(&next));
+                previous = Some(Arc::clone(current));
+                next
+            }
+            None => {
+                published = None;
+                Arc::clone(current)
+            }
+        });
+        if let (Some(layers), Some(previous)) = (&published, &previous) {
+            self.record(layers);
+            self.notify(previous, layers);
         }
-        self.layers.store(Arc::new(Layers::with_trie(merged)));
+        published
+    }
+
+    /// Add a published snapshot to the bounded history ring.
+    fn record(&self, layers: &Arc<Layers>) {
+        self.history.rcu(|history| {
+            let mut history = VecDeque::clone(history);
+            // Racing writers may record out of order — keep it sorted.
+            let pos = history.partition_point(|l| l.version < layers.version);
+            history.insert(pos, Arc::clone(layers));
+            trim_history(&mut history, self.history_limit);
+            history
+        });
     }
 
     /// Number of dynamic trie layers (excludes the base).
     pub fn layer_count(&self) -> usize {
         self.layers.load().len()
     }
+
+    /// Compact in a background thread every `interval`, whenever a source has
+    /// more than one permanent layer or an expired TTL layer needs a sweep.
+    ///
+    /// The thread holds only a weak reference and exits once the list is
+    /// dropped or the returned [`Compactor`] is stopped or dropped.
+    pub fn spawn_compactor(self: &Arc<Self>, interval: Duration) -> Compactor {
+        let list = Arc::downgrade(self);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = Arc::clone(&stop);
+            std::thread::Builder::new()
+                .name("blocklist-compactor".into())
+                .spawn(move || run_compactor(list, stop, interval))
+                .expect("failed to spawn compactor thread")
+        };
+        Compactor {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+fn run_compactor(list: Weak<DynamicBlockList>, stop: Arc<AtomicBool>, interval: Duration) {
+    loop {
+        std::thread::park_timeout(interval);
+        if stop.load(Ordering::Acquire) {
+            return;
+        }
+        let Some(list) = list.upgrade() else {
+            return;
+        };
+        let stats = list.stats();
+        if stats.permanent_layers > stats.sources || stats.expired_layers > 0 {
+            list.compact();
+        }
+    }
+}
+
+/// Handle to a background compaction thread started by
+/// [`DynamicBlockList::spawn_compactor`]. Stops the thread when dropped.
+pub struct Compactor {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Compactor {
+    /// Stop the background thread and wait for it to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.stop.store(true, Ordering::Release);
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Compactor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 impl Default for DynamicBlockList {
@@ -162,6 +873,27 @@ impl Default for DynamicBlockList {
     }
 }
 
+/// Lets one list serve as the base of another, e.g. a shared tenant-wide
+/// list under a per-crawl one.
+impl PrefixMatcher for DynamicBlockList {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        self.blocked_by(text)
+    }
+
+    #[inline]
+    fn contains_prefix(&self, text: &str) -> bool {
+        self.is_blocked(text)
+    }
+}
+
+/// Drop the oldest snapshots until at most `limit` remain.
+fn trim_history(history: &mut VecDeque<Arc<Layers>>, limit: usize) {
+    while history.len() > limit {
+        history.pop_front();
+    }
+}
+
 /// Walk `source` and re-insert every stored word into `dest`.
 fn collect_into(source: &Trie, dest: &mut Trie) {
     source.for_each_word(|bytes| {
@@ -226,6 +958,34 @@ mod tests {
         assert!(!bl.is_blocked("https://cdn.example.com/x"));
     }
 
+    #[test]
+    fn test_runtime_and_multiple_bases() {
+        use crate::scripts::{URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_TRIE};
+
+        let mut loaded = Trie::new();
+        loaded.insert("https://from-config.example.com/");
+        loaded.shrink_to_fit();
+        let loaded = Arc::new(loaded);
+
+        let bl = DynamicBlockList::with_base(Arc::clone(&loaded))
+            .and_base(URL_IGNORE_TRIE)
+            .and_base(URL_IGNORE_EMBEDED_TRIE);
+        assert_eq!(bl.base_count(), 3);
+        assert!(bl.is_blocked("https://from-config.example.com/x"));
+        assert!(bl.is_blocked("https://www.googletagmanager.com/gtm.js"));
+        assert!(bl.is_blocked("https://www.youtube.com/embed/abc"));
+        assert!(!bl.is_blocked("https://example.com/"));
+
+        // Another dynamic list works as a base too.
+        let shared = Arc::new(DynamicBlockList::from_patterns([
+            "https://tenant.example.com/",
+        ]));
+        let child = DynamicBlockList::with_base(Arc::clone(&shared));
+        assert!(child.is_blocked("https://tenant.example.com/x"));
+        shared.extend(["https://late.example.com/"]);
+        assert!(child.is_blocked("https://late.example.com/x"));
+    }
+
     #[test]
     fn test_seed_does_not_affect_base() {
         static BASE: LazyLock<Trie> = LazyLock::new(|| {
@@ -249,7 +1009,7 @@ mod tests {
     fn test_with_real_static_trie() {
         use crate::scripts::URL_IGNORE_TRIE;
 
-        let bl = DynamicBlockList::with_base(&URL_IGNORE_TRIE);
+        let bl = DynamicBlockList::with_base(URL_IGNORE_TRIE);
         bl.extend(["https://my-custom-tracker.example.com/"]);
 
         // Static patterns still work
@@ -260,6 +1020,25 @@ mod tests {
         assert!(!bl.is_blocked("https://cdn.example.com/app.js"));
     }
 
+    #[test]
+    fn test_blocked_by_reports_pattern() {
+        use crate::scripts::URL_IGNORE_TRIE;
+
+        let bl = DynamicBlockList::with_base(URL_IGNORE_TRIE);
+        bl.extend(["https://my-custom-tracker.example.com/"]);
+
+        let m = bl
+            .blocked_by("https://static.hotjar.com/c/hotjar-12345.js")
+            .unwrap();
+        assert_eq!(m.pattern, "https://static.hotjar.com/");
+        let m = bl
+            .blocked_by("https://my-custom-tracker.example.com/pixel")
+            .unwrap();
+        assert_eq!(m.pattern, "https://my-custom-tracker.example.com/");
+        assert_eq!(m.len, m.pattern.len());
+        assert!(bl.blocked_by("https://cdn.example.com/app.js").is_none());
+    }
+
     #[test]
     fn test_seed_replaces() {
         let bl = DynamicBlockList::from_patterns(["https://old.example.com/"]);
@@ -332,6 +1111,309 @@ mod tests {
         assert!(bl.is_blocked("https://new.example.com/y"));
     }
 
+    #[test]
+    fn test_remove_single_pattern() {
+        let bl = DynamicBlockList::from_patterns([
+            "https://ads.example.com/",
+            "https://tracker.example.com/",
+        ]);
+        bl.extend(["https://pixel.example.com/"]);
+
+        assert!(bl.remove("https://tracker.example.com/"));
+        assert!(!bl.is_blocked("https://tracker.example.com/x"));
+        assert!(bl.is_blocked("https://ads.example.com/x"));
+        assert!(bl.is_blocked("https://pixel.example.com/x"));
+        assert_eq!(bl.layer_count(), 2);
+
+        // Removing the only pattern of a layer drops the layer.
+        assert!(bl.remove("https://pixel.example.com/"));
+        assert_eq!(bl.layer_count(), 1);
+
+        // Only exact patterns are removed, never prefixes of them.
+        assert!(!bl.remove("https://ads.example.com/x"));
+        assert!(!bl.remove("https://unknown.example.com/"));
+        assert!(bl.is_blocked("https://ads.example.com/x"));
+    }
+
+    #[test]
+    fn test_allow_overrides_base_and_layers() {
+        use crate::scripts::URL_IGNORE_TRIE;
+
+        let bl = DynamicBlockList::with_base(URL_IGNORE_TRIE);
+        bl.extend(["https://ads.example.com/"]);
+        assert!(bl.is_blocked("https://static.hotjar.com/c/hotjar-1.js"));
+
+        bl.allow([
+            "https://static.hotjar.com/c/",
+            "https://ads.example.com/ok/",
+        ]);
+        assert!(!bl.is_blocked("https://static.hotjar.com/c/hotjar-1.js"));
+        assert!(bl
+            .blocked_by("https://static.hotjar.com/c/hotjar-1.js")
+            .is_none());
+        assert!(bl.is_blocked("https://static.hotjar.com/other.js"));
+        assert!(!bl.is_blocked("https://ads.example.com/ok/banner.js"));
+        assert!(bl.is_blocked("https://ads.example.com/banner.js"));
+
+        // Allow overrides survive seed and compact.
+        bl.seed(["https://ads.example.com/"]);
+        bl.extend(["https://pixel.example.com/"]);
+        bl.compact();
+        assert!(!bl.is_blocked("https://ads.example.com/ok/banner.js"));
+
+        assert!(bl.remove_allow("https://ads.example.com/ok/"));
+        assert!(bl.is_blocked("https://ads.example.com/ok/banner.js"));
+        assert!(!bl.remove_allow("https://ads.example.com/ok/"));
+    }
+
+    #[test]
+    fn test_ttl_patterns_expire() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        bl.extend_with_ttl(["https://hang.example.com/"], Duration::from_millis(50));
+        bl.extend_with_ttl(["https://later.example.com/"], Duration::from_secs(3600));
+        assert!(bl.is_blocked("https://hang.example.com/socket"));
+        assert_eq!(bl.layer_count(), 3);
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(!bl.is_blocked("https://hang.example.com/socket"));
+        assert!(bl.blocked_by("https://hang.example.com/socket").is_none());
+        assert!(bl.is_blocked("https://later.example.com/x"));
+        assert!(bl.is_blocked("https://ads.example.com/x"));
+        // Still present until swept.
+        assert_eq!(bl.layer_count(), 3);
+
+        assert_eq!(bl.purge_expired(), 1);
+        assert_eq!(bl.layer_count(), 2);
+        assert_eq!(bl.purge_expired(), 0);
+    }
+
+    #[test]
+    fn test_compact_sweeps_expired_and_keeps_live_ttl() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        bl.extend(["https://tracker.example.com/"]);
+        bl.extend_with_ttl(["https://gone.example.com/"], Duration::ZERO);
+        bl.extend_with_ttl(["https://live.example.com/"], Duration::from_secs(3600));
+
+        bl.compact();
+        // One merged permanent layer plus the live TTL layer.
+        assert_eq!(bl.layer_count(), 2);
+        assert!(bl.is_blocked("https://ads.example.com/x"));
+        assert!(bl.is_blocked("https://tracker.example.com/x"));
+        assert!(bl.is_blocked("https://live.example.com/x"));
+        assert!(!bl.is_blocked("https://gone.example.com/x"));
+    }
+
+    #[test]
+    fn test_versions_increase_and_rollback_restores() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        let v0 = bl.version();
+        bl.extend(["https://tracker.example.com/"]);
+        let good = bl.snapshot();
+        assert!(good.version() > v0);
+
+        // A bad push over-blocks everything.
+        bl.seed(["https://"]);
+        assert!(bl.version() > good.version());
+        assert!(bl.is_blocked("https://cdn.example.com/app.js"));
+
+        assert!(bl.rollback(good.version()));
+        assert!(!bl.is_blocked("https://cdn.example.com/app.js"));
+        assert!(bl.is_blocked("https://tracker.example.com/x"));
+        assert_eq!(bl.layer_count(), good.layer_count());
+        // Rolling back is itself a new version.
+        let after = bl.version();
+        assert!(after > good.version());
+        assert_eq!(bl.versions().last(), Some(&after));
+
+        assert!(good.is_blocked("https://ads.example.com/x"));
+        assert!(!bl.rollback(u64::MAX));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let bl = DynamicBlockList::new().with_history_limit(3);
+        let first = bl.version();
+        for i in 0..10 {
+            bl.extend([format!("https://t{i}.example.com/").as_str()]);
+        }
+        let versions = bl.versions();
+        assert_eq!(versions.len(), 3);
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*versions.last().unwrap(), bl.version());
+        assert!(!bl.rollback(first));
+
+        // No-op changes do not publish a version.
+        let v = bl.version();
+        assert!(!bl.remove("https://missing.example.com/"));
+        assert_eq!(bl.version(), v);
+    }
+
+    #[test]
+    fn test_stats_report_layer_shape() {
+        let bl =
+            DynamicBlockList::from_patterns(["https://a.example.com/", "https://b.example.com/"]);
+        bl.extend(["https://c.example.com/"]);
+        bl.extend_with_ttl(["https://d.example.com/"], Duration::ZERO);
+        bl.allow(["https://a.example.com/ok/"]);
+
+        let stats = bl.stats();
+        assert_eq!(stats.layers, 3);
+        assert_eq!(stats.permanent_layers, 2);
+        assert_eq!(stats.sources, 1);
+        assert_eq!(stats.ttl_layers, 1);
+        assert_eq!(stats.expired_layers, 1);
+        assert_eq!(stats.allow_layers, 1);
+        assert_eq!(stats.patterns, 4);
+        assert_eq!(stats.uncompacted_patterns, 1);
+        assert_eq!(stats.probes_per_lookup(), 4);
+    }
+
+    #[test]
+    fn test_lookup_reports_source() {
+        let bl = DynamicBlockList::with_base(crate::scripts::URL_IGNORE_TRIE);
+        bl.extend_tagged("easylist", ["https://promo.example.com/"]);
+        bl.extend_tagged("customer", ["https://track.example.com/"]);
+        bl.extend(["https://plain.example.com/"]);
+
+        let m = bl.lookup("https://promo.example.com/a.js").unwrap();
+        assert_eq!(m.pattern, "https://promo.example.com/");
+        assert_eq!(m.source.as_deref(), Some("easylist"));
+        assert!(!m.base);
+        let m = bl.lookup("https://track.example.com/").unwrap();
+        assert_eq!(m.source.as_deref(), Some("customer"));
+        let m = bl.lookup("https://plain.example.com/").unwrap();
+        assert_eq!(m.source, None);
+        let m = bl
+            .lookup("https://www.googletagmanager.com/gtm.js?id=1")
+            .unwrap();
+        assert!(m.base);
+        assert!(bl.lookup("https://example.com/").is_none());
+    }
+
+    #[test]
+    fn test_hit_counters() {
+        let bl = DynamicBlockList::with_base(crate::scripts::URL_IGNORE_TRIE);
+        bl.extend_tagged("easylist", ["https://promo.example.com/"]);
+        bl.extend_tagged(
+            "customer",
+            ["https://track.example.com/", "https://pixel.example.com/"],
+        );
+        bl.allow(["https://promo.example.com/ok/"]);
+
+        for _ in 0..3 {
+            assert!(bl.is_blocked("https://promo.example.com/a.js"));
+        }
+        assert!(bl.is_blocked("https://track.example.com/"));
+        assert!(!bl.is_blocked("https://promo.example.com/ok/a.js"));
+        assert!(bl.is_blocked("https://www.googletagmanager.com/gtm.js?id=1"));
+
+        let hits = bl.layer_hits();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].source.as_deref(), Some("easylist"));
+        assert_eq!(hits[0].hits, 3);
+        assert_eq!(hits[1].hits, 1);
+        assert!(hits[2].allow);
+        assert_eq!(hits[2].hits, 1);
+        assert_eq!(bl.base_hits(), 1);
+
+        // Counts survive merging and pattern removal.
+        bl.extend_tagged("easylist", ["https://more.example.com/"]);
+        assert!(bl.remove("https://pixel.example.com/"));
+        bl.compact();
+        let hits = bl.layer_hits();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].patterns, 2);
+        assert_eq!(hits[0].hits, 3);
+        assert_eq!(hits[1].source.as_deref(), Some("customer"));
+        assert_eq!(hits[1].patterns, 1);
+        assert_eq!(hits[1].hits, 1);
+    }
+
+    #[test]
+    fn test_compact_keeps_sources_apart() {
+        let bl = DynamicBlockList::new();
+        bl.extend_tagged("a", ["https://a1.example.com/"]);
+        bl.extend_tagged("b", ["https://b1.example.com/"]);
+        bl.extend_tagged("a", ["https://a2.example.com/"]);
+        assert_eq!(bl.stats().sources, 2);
+        assert_eq!(bl.stats().uncompacted_patterns, 1);
+
+        bl.compact();
+        assert_eq!(bl.layer_count(), 2);
+        let v = bl.version();
+        // One layer per source is already compact.
+        bl.compact();
+        assert_eq!(bl.version(), v);
+        assert_eq!(
+            bl.lookup("https://a2.example.com/")
+                .unwrap()
+                .source
+                .as_deref(),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn test_seed_tagged_replaces_one_source() {
+        let bl = DynamicBlockList::new();
+        bl.extend_tagged("a", ["https://a1.example.com/"]);
+        bl.extend_tagged("b", ["https://b1.example.com/"]);
+        bl.seed_tagged("a", ["https://a2.example.com/"]);
+        assert!(!bl.is_blocked("https://a1.example.com/"));
+        assert!(bl.is_blocked("https://a2.example.com/"));
+        assert!(bl.is_blocked("https://b1.example.com/"));
+    }
+
+    #[test]
+    fn test_compaction_policy_max_layers() {
+        let policy = CompactionPolicy {
+            max_layers: Some(3),
+            ..CompactionPolicy::default()
+        };
+        let bl = DynamicBlockList::new().with_compaction_policy(policy);
+        assert_eq!(bl.compaction_policy(), policy);
+        for i in 0..3 {
+            bl.extend([format!("https://t{i}.example.com/").as_str()]);
+        }
+        assert_eq!(bl.layer_count(), 3);
+        bl.extend(["https://t3.example.com/"]);
+        assert_eq!(bl.layer_count(), 1);
+        for i in 0..4 {
+            assert!(bl.is_blocked(&format!("https://t{i}.example.com/x")));
+        }
+    }
+
+    #[test]
+    fn test_compaction_policy_max_uncompacted_patterns() {
+        let policy = CompactionPolicy {
+            max_uncompacted_patterns: Some(2),
+            ..CompactionPolicy::default()
+        };
+        let bl = DynamicBlockList::from_patterns(["https://base.example.com/"])
+            .with_compaction_policy(policy);
+        bl.extend(["https://a.example.com/", "https://b.example.com/"]);
+        assert_eq!(bl.layer_count(), 2);
+        bl.extend(["https://c.example.com/"]);
+        assert_eq!(bl.layer_count(), 1);
+        assert_eq!(bl.stats().uncompacted_patterns, 0);
+    }
+
+    #[test]
+    fn test_background_compactor() {
+        let bl = Arc::new(DynamicBlockList::from_patterns(["https://a.example.com/"]));
+        bl.extend(["https://b.example.com/"]);
+        bl.extend(["https://c.example.com/"]);
+        let compactor = bl.spawn_compactor(Duration::from_millis(5));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while bl.layer_count() > 1 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        compactor.stop();
+        assert_eq!(bl.layer_count(), 1);
+        assert!(bl.is_blocked("https://c.example.com/x"));
+    }
+
     #[test]
     fn test_concurrent_reads_during_extend() {
         use std::sync::Arc;
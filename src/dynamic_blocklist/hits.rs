@@ -0,0 +1,82 @@
+//! Hit counters for [`DynamicBlockList`](super::DynamicBlockList).
+//!
+//! Every matching lookup bumps a counter, so a single shared atomic would
+//! bounce its cache line between every thread checking requests. Each
+//! counter is instead split into cache-padded stripes; a thread always adds
+//! to the same stripe and reads sum them.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Stripes per counter. Threads beyond this share stripes round-robin.
+const STRIPES: usize = 8;
+
+/// One stripe, padded to its own pair of cache lines so neighbours never
+/// false-share (adjacent-line prefetch pulls lines in pairs).
+#[derive(Default)]
+#[repr(align(128))]
+struct Stripe(AtomicU64);
+
+/// A striped event counter. Relaxed throughout — a statistic, not a sync
+/// point.
+#[derive(Default)]
+pub(super) struct HitCounter {
+    stripes: [Stripe; STRIPES],
+}
+
+impl HitCounter {
+    /// Count one hit.
+    #[inline]
+    pub(super) fn add(&self) {
+        self.stripes[stripe()].0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Hits counted so far.
+    pub(super) fn get(&self) -> u64 {
+        self.stripes
+            .iter()
+            .map(|s| s.0.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Reset the count to `hits`. Only meant for a counter no reader can
+    /// reach yet — concurrent `add`s may be lost.
+    pub(super) fn set(&self, hits: u64) {
+        for (i, s) in self.stripes.iter().enumerate() {
+            s.0.store(if i == 0 { hits } else { 0 }, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The calling thread's stripe, assigned round-robin on first use.
+#[inline]
+fn stripe() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static STRIPE: usize = NEXT.fetch_add(1, Ordering::Relaxed) % STRIPES;
+    }
+    STRIPE.with(|s| *s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn counts_across_threads() {
+        let counter = Arc::new(HitCounter::default());
+        let threads: Vec<_> = (0..STRIPES * 2)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                std::thread::spawn(move || (0..1000).for_each(|_| counter.add()))
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert_eq!(counter.get(), STRIPES as u64 * 2000);
+
+        counter.set(7);
+        assert_eq!(counter.get(), 7);
+    }
+}
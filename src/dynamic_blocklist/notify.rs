@@ -0,0 +1,188 @@
+//! Change notifications for [`DynamicBlockList`].
+//!
+//! Subscribers are plain callbacks kept in an `ArcSwap`'d list, so
+//! subscribing never blocks readers or writers. They run on the writing
+//! thread right after a new snapshot is published; a list with no
+//! subscribers skips the diff entirely. Nothing serializes concurrent
+//! writers' callbacks, so they may arrive out of version order.
+
+use super::{DynamicBlockList, Layer, Layers};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Callback registered with [`DynamicBlockList::subscribe`].
+pub(super) type Subscriber = Arc<dyn Fn(&BlockListChange) + Send + Sync>;
+
+/// What changed in one published version of a [`DynamicBlockList`].
+///
+/// Pattern lists are sorted and only name patterns that actually entered or
+/// left the list — a `compact` publishes a version with all four empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockListChange {
+    /// Version of the newly published snapshot. Versions increase with
+    /// each publish but may skip numbers, and changes made concurrently may
+    /// be delivered out of order — order by this field, not by arrival.
+    pub version: u64,
+    /// Block patterns now present that were not before.
+    pub added: Vec<String>,
+    /// Block patterns no longer present.
+    pub removed: Vec<String>,
+    /// Allow overrides now present that were not before.
+    pub allow_added: Vec<String>,
+    /// Allow overrides no longer present.
+    pub allow_removed: Vec<String>,
+}
+
+/// Handle returned by [`DynamicBlockList::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+impl DynamicBlockList {
+    /// Call `f` after every change — `seed`, `extend`, `swap`, `compact`,
+    /// removals, allow overrides, TTL sweeps, loads and rollbacks.
+    ///
+    /// `f` runs synchronously on the thread that made the change, so keep
+    /// it short (e.g. push into a channel). With several writing threads,
+    /// `f` can run concurrently and see a newer version before an older
+    /// one; callers that need order must sort by
+    /// [`BlockListChange::version`].
+    pub fn subscribe<F>(&self, f: F) -> SubscriptionId
+    where
+        F: Fn(&BlockListChange) + Send + Sync + 'static,
+    {
+        let id = self.next_subscriber.fetch_add(1, Ordering::Relaxed);
+        let f: Subscriber = Arc::new(f);
+        self.subscribers.rcu(|subs| {
+            let mut subs = Vec::clone(subs);
+            subs.push((id, Arc::clone(&f)));
+            subs
+        });
+        SubscriptionId(id)
+    }
+
+    /// Remove a subscriber. Returns `false` if `id` was not subscribed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut found = false;
+        self.subscribers.rcu(|subs| {
+            found = subs.iter().any(|(sid, _)| *sid == id.0);
+            subs.iter()
+                .filter(|(sid, _)| *sid != id.0)
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        found
+    }
+
+    /// Tell subscribers that `previous` was replaced by `next`.
+    pub(super) fn notify(&self, previous: &Layers, next: &Layers) {
+        let subs = self.subscribers.load();
+        if subs.is_empty() {
+            return;
+        }
+        let (added, removed) = diff(&previous.tries, &next.tries);
+        let (allow_added, allow_removed) = diff(&previous.allow, &next.allow);
+        let change = BlockListChange {
+            version: next.version,
+            added,
+            removed,
+            allow_added,
+            allow_removed,
+        };
+        for (_, f) in subs.iter() {
+            f(&change);
+        }
+    }
+}
+
+/// Patterns added and removed going from `before` to `after`.
+///
+/// Layers shared between the two snapshots can't contribute, so only the
+/// layers unique to each side are enumerated; each candidate is then checked
+/// for an exact match on the other side.
+fn diff(before: &[Arc<Layer>], after: &[Arc<Layer>]) -> (Vec<String>, Vec<String>) {
+    let only = |side: &[Arc<Layer>], other: &[Arc<Layer>]| -> Vec<String> {
+        let mut out = Vec::new();
+        for layer in side {
+            if other.iter().any(|o| Arc::ptr_eq(o, layer)) {
+                continue;
+            }
+            layer.trie.for_each_word(|bytes| {
+                if let Ok(word) = std::str::from_utf8(bytes) {
+                    if !other.iter().any(|o| o.trie.get(word).is_some()) {
+                        out.push(word.to_string());
+                    }
+                }
+            });
+        }
+        out.sort_unstable();
+        out.dedup();
+        out
+    };
+    (only(after, before), only(before, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording(bl: &DynamicBlockList) -> (SubscriptionId, Arc<Mutex<Vec<BlockListChange>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let id = bl.subscribe(move |change| sink.lock().unwrap().push(change.clone()));
+        (id, seen)
+    }
+
+    #[test]
+    fn reports_added_and_removed_patterns() {
+        let bl = DynamicBlockList::from_patterns(["https://a.example.com/"]);
+        let (_, seen) = recording(&bl);
+
+        bl.extend(["https://b.example.com/", "https://a.example.com/"]);
+        bl.remove("https://a.example.com/");
+        bl.seed(["https://c.example.com/"]);
+        bl.allow(["https://c.example.com/ok/"]);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 4);
+        assert_eq!(seen[0].added, ["https://b.example.com/"]);
+        assert!(seen[0].removed.is_empty());
+        // `remove` drops the pattern from every layer storing it.
+        assert_eq!(seen[1].removed, ["https://a.example.com/"]);
+        assert!(seen[1].added.is_empty());
+        assert_eq!(seen[2].added, ["https://c.example.com/"]);
+        assert_eq!(seen[2].removed, ["https://b.example.com/"]);
+        assert_eq!(seen[3].allow_added, ["https://c.example.com/ok/"]);
+        assert_eq!(seen[3].version, bl.version());
+        assert!(seen.windows(2).all(|w| w[0].version < w[1].version));
+    }
+
+    #[test]
+    fn compact_reports_no_pattern_changes() {
+        let bl = DynamicBlockList::from_patterns(["https://a.example.com/"]);
+        bl.extend(["https://b.example.com/"]);
+        let (_, seen) = recording(&bl);
+
+        bl.compact();
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(
+            seen[0],
+            BlockListChange {
+                version: bl.version(),
+                ..BlockListChange::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unsubscribe_stops_notifications() {
+        let bl = DynamicBlockList::new();
+        let (id, seen) = recording(&bl);
+        bl.extend(["https://a.example.com/"]);
+        assert!(bl.unsubscribe(id));
+        assert!(!bl.unsubscribe(id));
+        bl.extend(["https://b.example.com/"]);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,710 @@
+//! On-disk formats for [`DynamicBlockList`].
+//!
+//! Binary (`save_to` / `load_from`), all integers little-endian:
+//!
+//! ```text
+//! magic    b"SNBL"
+//! version  u8            (FORMAT_VERSION)
+//! count    u32           number of records
+//! records  count × {
+//!            kind    u8
+//!            expires u64   unix secs, only if kind == TTL
+//!            source  u16 len + bytes (len 0 = untagged)
+//!            pattern u32 len + bytes
+//!          }
+//! crc32    u32           IEEE CRC-32 of every preceding byte
+//! ```
+//!
+//! Text (`save_text_to` / `load_text_from`): one block pattern per line,
+//! `!allow <pattern>` for allow overrides, `!source <label>` to tag the
+//! block patterns that follow (a bare `!source` goes back to untagged), `#`
+//! comments. Sorted, so two saves diff cleanly. TTL patterns are temporary
+//! by nature and only kept by the binary format.
+//!
+//! A pattern that would read back as something else — one starting with
+//! `#`, `!` or `\`, or with leading or trailing whitespace — is written
+//! with a `\` in front and read back verbatim. Patterns containing a line
+//! break, and source labels that are empty, contain a line break or start
+//! or end with whitespace, can't be written as text. Any other line
+//! starting with `!` is an error on load.
+//!
+//! Both formats store the merged dynamic layers — never the base trie — and
+//! are written to a temporary file first, then renamed into place.
+
+use super::{DynamicBlockList, Layer, Layers};
+use crate::trie::Trie;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 4] = b"SNBL";
+/// Bumped on any incompatible layout change.
+const FORMAT_VERSION: u8 = 1;
+
+const KIND_BLOCK: u8 = 0;
+const KIND_ALLOW: u8 = 1;
+const KIND_TTL: u8 = 2;
+
+/// Text-format directive for allow overrides.
+const ALLOW_DIRECTIVE: &str = "!allow";
+/// Text-format prefix for allow overrides.
+const ALLOW_PREFIX: &str = "!allow ";
+/// Text-format directive tagging the block patterns that follow.
+const SOURCE_DIRECTIVE: &str = "!source";
+/// Text-format prefix marking the rest of the line as a literal pattern.
+const ESCAPE: char = '\\';
+
+/// Patterns of the current snapshot, merged across layers.
+#[derive(Default)]
+struct Contents {
+    /// Permanent block patterns as `(source, pattern)`, sorted.
+    block: Vec<(Option<String>, String)>,
+    allow: Vec<String>,
+    /// Live TTL patterns as `(deadline, source, pattern)`, the deadline in
+    /// wall-clock unix seconds.
+    ttl: Vec<(u64, Option<String>, String)>,
+}
+
+impl DynamicBlockList {
+    /// Save the dynamic layers (block, allow and live TTL patterns) in the
+    /// compact binary format.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomic(path.as_ref(), &encode(&self.contents()))
+    }
+
+    /// Replace the dynamic layers with the contents of a file written by
+    /// [`save_to`](Self::save_to). The base trie (if any) is unaffected.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] on a bad magic, an unknown
+    /// format version, a checksum mismatch or truncated data; the list is
+    /// left untouched in that case.
+    pub fn load_from(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = decode(&std::fs::read(path)?)?;
+        self.restore(contents);
+        Ok(())
+    }
+
+    /// Save the permanent block patterns and allow overrides as sorted
+    /// plain text, one per line.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`], writing nothing, if a
+    /// pattern contains a line break or a source label wouldn't read back
+    /// as itself.
+    pub fn save_text_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = self.contents();
+        let mut patterns = contents.block.iter().map(|(_, p)| p).chain(&contents.allow);
+        if let Some(p) = patterns.find(|p| p.contains(['\n', '\r'])) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("pattern {:?} contains a line break", p),
+            ));
+        }
+        let mut labels = contents.block.iter().filter_map(|(s, _)| s.as_deref());
+        if let Some(label) =
+            labels.find(|l| l.trim() != *l || l.is_empty() || l.contains(['\n', '\r']))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("source label {:?} can't be written as text", label),
+            ));
+        }
+        let mut out = String::from("# spider_network_blocker dynamic block list\n");
+        let mut current: Option<&str> = None;
+        for (source, p) in &contents.block {
+            if source.as_deref() != current {
+                current = source.as_deref();
+                out.push_str(SOURCE_DIRECTIVE);
+                if let Some(label) = current {
+                    out.push(' ');
+                    out.push_str(label);
+                }
+                out.push('\n');
+            }
+            push_pattern(&mut out, p);
+        }
+        for p in &contents.allow {
+            out.push_str(ALLOW_PREFIX);
+            push_pattern(&mut out, p);
+        }
+        write_atomic(path.as_ref(), out.as_bytes())
+    }
+
+    /// Replace the dynamic layers with the contents of a plain-text file.
+    /// Blank lines and `#` comments are skipped.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] naming the line on an
+    /// unknown `!` directive or an `!allow` without a pattern; the list is
+    /// left untouched in that case.
+    pub fn load_text_from(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = std::fs::read_to_string(path)?;
+        let mut contents = Contents::default();
+        let mut source = None;
+        for (i, raw) in text.lines().enumerate() {
+            if let Some(p) = raw.strip_prefix(ESCAPE) {
+                contents.block.push((source.clone(), p.to_string()));
+                continue;
+            }
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if !line.starts_with('!') {
+                contents.block.push((source.clone(), line.to_string()));
+                continue;
+            }
+            let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            match directive {
+                SOURCE_DIRECTIVE => {
+                    let label = rest.trim();
+                    source = (!label.is_empty()).then(|| label.to_string());
+                }
+                ALLOW_DIRECTIVE => {
+                    let p = match raw.trim_start().strip_prefix(ALLOW_PREFIX) {
+                        Some(p) => p.strip_prefix(ESCAPE).unwrap_or(p.trim()),
+                        None => rest.trim(),
+                    };
+                    if p.is_empty() {
+                        return Err(invalid(format!("line {}: `!allow` needs a pattern", i + 1)));
+                    }
+                    contents.allow.push(p.to_string());
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "line {}: unknown directive `{}`",
+                        i + 1,
+                        directive
+                    )))
+                }
+            }
+        }
+        self.restore(contents);
+        Ok(())
+    }
+
+    fn contents(&self) -> Contents {
+        let layers = self.layers.load();
+        let instant_now = Instant::now();
+        let wall_now = SystemTime::now();
+
+        let mut block: BTreeMap<Option<String>, Trie> = BTreeMap::new();
+        let mut allow = Trie::new();
+        let mut ttl: BTreeMap<(Option<String>, String), u64> = BTreeMap::new();
+        for layer in &layers.tries {
+            let source = layer.source.as_deref().map(str::to_string);
+            match layer.expires_at {
+                None => super::collect_into(&layer.trie, block.entry(source).or_default()),
+                Some(at) if at > instant_now => {
+                    let deadline = unix_secs(wall_now + (at - instant_now));
+                    for p in words(&layer.trie) {
+                        // Keep the latest deadline when a pattern repeats.
+                        let entry = ttl.entry((source.clone(), p)).or_insert(deadline);
+                        *entry = (*entry).max(deadline);
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+        for layer in &layers.allow {
+            super::collect_into(&layer.trie, &mut allow);
+        }
+
+        Contents {
+            block: block
+                .into_iter()
+                .flat_map(|(source, trie)| {
+                    sorted_words(&trie)
+                        .into_iter()
+                        .map(move |p| (source.clone(), p))
+                })
+                .collect(),
+            allow: sorted_words(&allow),
+            ttl: ttl
+                .into_iter()
+                .map(|((source, p), at)| (at, source, p))
+                .collect(),
+        }
+    }
+
+    /// Publish `contents` as the new dynamic layers in one version.
+    fn restore(&self, contents: Contents) {
+        // One permanent layer per source.
+        let mut block: BTreeMap<&Option<String>, Trie> = BTreeMap::new();
+        for (source, p) in &contents.block {
+            block.entry(source).or_default().insert(p);
+        }
+        let mut allow = Trie::new();
+        for p in &contents.allow {
+            allow.insert(p);
+        }
+
+        // Group TTL patterns by deadline and source so each becomes one layer.
+        let instant_now = Instant::now();
+        let wall_now = unix_secs(SystemTime::now());
+        let mut by_deadline: BTreeMap<(u64, &Option<String>), Trie> = BTreeMap::new();
+        for (at, source, p) in &contents.ttl {
+            if *at > wall_now {
+                by_deadline.entry((*at, source)).or_default().insert(p);
+            }
+        }
+
+        let mut tries = Vec::with_capacity(block.len() + by_deadline.len());
+        for (source, trie) in block {
+            tries.push(Layer::new(trie, None, source.as_deref().map(Arc::from)));
+        }
+        for ((at, source), trie) in by_deadline {
+            let expires_at = instant_now.checked_add(Duration::from_secs(at - wall_now));
+            tries.push(Layer::new(
+                trie,
+                expires_at,
+                source.as_deref().map(Arc::from),
+            ));
+        }
+        let allow = if allow.is_empty() {
+            Vec::new()
+        } else {
+            vec![Layer::new(allow, None, None)]
+        };
+        self.publish(|_| {
+            Some(Layers {
+                version: 0,
+                tries: tries.clone(),
+                allow: allow.clone(),
+            })
+        });
+    }
+}
+
+fn encode(contents: &Contents) -> Vec<u8> {
+    let count = contents.block.len() + contents.allow.len() + contents.ttl.len();
+    let mut out = Vec::with_capacity(16 + count * 32);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&(count as u32).to_le_bytes());
+
+    for (source, p) in &contents.block {
+        write_record(&mut out, KIND_BLOCK, None, source.as_deref(), p);
+    }
+    for p in &contents.allow {
+        write_record(&mut out, KIND_ALLOW, None, None, p);
+    }
+    for (at, source, p) in &contents.ttl {
+        write_record(&mut out, KIND_TTL, Some(*at), source.as_deref(), p);
+    }
+
+    let crc = crc32(&out);
+    out.extend_from_slice(&crc.to_le_bytes());
+    out
+}
+
+fn write_record(
+    out: &mut Vec<u8>,
+    kind: u8,
+    expires: Option<u64>,
+    source: Option<&str>,
+    pattern: &str,
+) {
+    out.push(kind);
+    if let Some(at) = expires {
+        out.extend_from_slice(&at.to_le_bytes());
+    }
+    // Labels are short feed names; anything longer is cut at a char boundary.
+    let source = source.unwrap_or("");
+    let mut end = source.len().min(u16::MAX as usize);
+    while !source.is_char_boundary(end) {
+        end -= 1;
+    }
+    let source = &source[..end];
+    out.extend_from_slice(&(source.len() as u16).to_le_bytes());
+    out.extend_from_slice(source.as_bytes());
+    out.extend_from_slice(&(pattern.len() as u32).to_le_bytes());
+    out.extend_from_slice(pattern.as_bytes());
+}
+
+fn decode(data: &[u8]) -> io::Result<Contents> {
+    if data.len() < MAGIC.len() + 1 + 4 + 4 || &data[..4] != MAGIC {
+        return Err(invalid("not a dynamic block list file"));
+    }
+    if data[4] != FORMAT_VERSION {
+        return Err(invalid(format!(
+            "unsupported block list format version {}",
+            data[4]
+        )));
+    }
+    let (body, trailer) = data.split_at(data.len() - 4);
+    let expected = u32::from_le_bytes(trailer.try_into().expect("4-byte trailer"));
+    if crc32(body) != expected {
+        return Err(invalid("block list checksum mismatch"));
+    }
+
+    let mut reader = Reader { data: &body[5..] };
+    let count = reader.u32()?;
+    let mut contents = Contents::default();
+    for _ in 0..count {
+        let kind = reader.u8()?;
+        let expires = if kind == KIND_TTL {
+            Some(reader.u64()?)
+        } else {
+            None
+        };
+        let source_len = reader.u16()? as usize;
+        let source = std::str::from_utf8(reader.take(source_len)?)
+            .map_err(|_| invalid("source label is not valid UTF-8"))?;
+        let source = (!source.is_empty()).then(|| source.to_string());
+        let len = reader.u32()? as usize;
+        let pattern = std::str::from_utf8(reader.take(len)?)
+            .map_err(|_| invalid("pattern is not valid UTF-8"))?
+            .to_string();
+        match (kind, expires) {
+            (KIND_BLOCK, _) => contents.block.push((source, pattern)),
+            (KIND_ALLOW, _) => contents.allow.push(pattern),
+            (KIND_TTL, Some(at)) => contents.ttl.push((at, source, pattern)),
+            _ => return Err(invalid(format!("unknown record kind {kind}"))),
+        }
+    }
+    if !reader.data.is_empty() {
+        return Err(invalid("trailing bytes after last record"));
+    }
+    Ok(contents)
+}
+
+/// Bounds-checked little-endian cursor.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.data.len() < n {
+            return Err(invalid("truncated block list file"));
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(
+            self.take(2)?.try_into().expect("2 bytes"),
+        ))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().expect("4 bytes"),
+        ))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(
+            self.take(8)?.try_into().expect("8 bytes"),
+        ))
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Write to a sibling temp file, sync it, then rename over `path`, so a
+/// crash never leaves a half-written list behind. The temp name is unique
+/// per process and call, so concurrent saves to one path don't clobber each
+/// other's temp file; the last rename wins.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let write = || {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    };
+    let result = write();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Append `pattern` and a newline, escaped if it would otherwise read back
+/// differently.
+fn push_pattern(out: &mut String, pattern: &str) {
+    if pattern.starts_with(['#', '!', ESCAPE]) || pattern.trim() != pattern {
+        out.push(ESCAPE);
+    }
+    out.push_str(pattern);
+    out.push('\n');
+}
+
+fn words(trie: &Trie) -> Vec<String> {
+    let mut out = Vec::with_capacity(trie.len());
+    trie.for_each_word(|bytes| {
+        if let Ok(word) = std::str::from_utf8(bytes) {
+            out.push(word.to_string());
+        }
+    });
+    out
+}
+
+fn sorted_words(trie: &Trie) -> Vec<String> {
+    let mut out = words(trie);
+    out.sort_unstable();
+    out
+}
+
+fn unix_secs(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// IEEE CRC-32 (reflected, poly `0xEDB88320`), bitwise — files are small
+/// and only checked on load.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "spider_network_blocker_{}_{}",
+            std::process::id(),
+            name
+        ))
+    }
+
+    #[test]
+    fn concurrent_saves_leave_one_complete_file() {
+        let dir = temp_path("concurrent");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.bin");
+        let bl = Arc::new(DynamicBlockList::from_patterns([
+            "https://ads.example.com/",
+        ]));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let (bl, path) = (Arc::clone(&bl), path.clone());
+                std::thread::spawn(move || bl.save_to(&path))
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap().unwrap();
+        }
+
+        let restored = DynamicBlockList::new();
+        restored.load_from(&path).unwrap();
+        assert!(restored.is_blocked("https://ads.example.com/x"));
+        // Every temp file was renamed into place.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn crc32_known_vector() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        bl.extend(["https://tracker.example.com/"]);
+        bl.extend_with_ttl(["https://hang.example.com/"], Duration::from_secs(3600));
+        bl.extend_with_ttl(["https://gone.example.com/"], Duration::ZERO);
+        bl.allow(["https://ads.example.com/ok/"]);
+
+        let path = temp_path("roundtrip.bin");
+        bl.save_to(&path).unwrap();
+
+        let restored = DynamicBlockList::new();
+        restored.load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(restored.is_blocked("https://ads.example.com/x"));
+        assert!(restored.is_blocked("https://tracker.example.com/x"));
+        assert!(restored.is_blocked("https://hang.example.com/x"));
+        assert!(!restored.is_blocked("https://gone.example.com/x"));
+        assert!(!restored.is_blocked("https://ads.example.com/ok/x"));
+        // Merged permanent layer plus one TTL layer.
+        assert_eq!(restored.stats().permanent_layers, 1);
+        assert_eq!(restored.stats().ttl_layers, 1);
+    }
+
+    #[test]
+    fn corrupted_file_is_rejected() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        let path = temp_path("corrupt.bin");
+        bl.save_to(&path).unwrap();
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        let mid = bytes.len() / 2;
+        bytes[mid] ^= 0xFF;
+        std::fs::write(&path, &bytes).unwrap();
+
+        let restored = DynamicBlockList::from_patterns(["https://keep.example.com/"]);
+        let err = restored.load_from(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // Untouched on failure.
+        assert!(restored.is_blocked("https://keep.example.com/x"));
+
+        assert!(decode(b"nope").is_err());
+        let mut wrong_version = encode(&Contents::default());
+        wrong_version[4] = FORMAT_VERSION + 1;
+        assert!(decode(&wrong_version).is_err());
+    }
+
+    #[test]
+    fn sources_roundtrip() {
+        let bl = DynamicBlockList::new();
+        bl.extend_tagged("easylist", ["https://ads.example.com/"]);
+        bl.extend(["https://plain.example.com/"]);
+        bl.extend_tagged("easylist", ["https://more.example.com/"]);
+
+        let bin = temp_path("sources.bin");
+        let txt = temp_path("sources.txt");
+        bl.save_to(&bin).unwrap();
+        bl.save_text_to(&txt).unwrap();
+        let text = std::fs::read_to_string(&txt).unwrap();
+        assert!(text.contains("!source easylist\nhttps://ads.example.com/\n"));
+
+        for (path, binary) in [(&bin, true), (&txt, false)] {
+            let restored = DynamicBlockList::new();
+            if binary {
+                restored.load_from(path).unwrap();
+            } else {
+                restored.load_text_from(path).unwrap();
+            }
+            std::fs::remove_file(path).unwrap();
+            assert_eq!(restored.layer_count(), 2);
+            let m = restored.lookup("https://more.example.com/x").unwrap();
+            assert_eq!(m.source.as_deref(), Some("easylist"));
+            let m = restored.lookup("https://plain.example.com/x").unwrap();
+            assert_eq!(m.source, None);
+        }
+    }
+
+    #[test]
+    fn text_roundtrip_escapes_special_patterns() {
+        let special = [
+            "#fragment",
+            "!allow https://a.example.com/",
+            "!source feed",
+            "\\server\\share",
+            " leading",
+            "trailing\t",
+        ];
+        let bl = DynamicBlockList::from_patterns(special);
+        bl.extend(["https://plain.example.com/"]);
+        bl.allow(["#allowed", " spaced "]);
+
+        let path = temp_path("escapes.txt");
+        bl.save_text_to(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("\n\\#fragment\n"), "{text}");
+        assert!(text.contains("\n!allow \\ spaced \n"), "{text}");
+        assert!(text.contains("\nhttps://plain.example.com/\n"), "{text}");
+
+        let restored = DynamicBlockList::new();
+        restored.load_text_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let saved = bl.contents();
+        let loaded = restored.contents();
+        assert_eq!(loaded.block, saved.block);
+        assert_eq!(loaded.allow, saved.allow);
+        assert_eq!(saved.block.len(), special.len() + 1);
+
+        let broken = DynamicBlockList::from_patterns(["a\nb"]);
+        let err = broken.save_text_to(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(!path.exists());
+
+        for label in ["feed\nhttps://x.example.com/", " feed", "feed ", ""] {
+            let broken = DynamicBlockList::new();
+            broken.extend_tagged(label, ["https://a.example.com/"]);
+            let err = broken.save_text_to(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{label:?}");
+            assert!(!path.exists());
+        }
+    }
+
+    #[test]
+    fn text_rejects_unknown_directives() {
+        let path = temp_path("directives.txt");
+        let bl = DynamicBlockList::from_patterns(["https://kept.example.com/"]);
+        for (text, line) in [
+            ("https://a.example.com/\n!sorce feed\n", 2),
+            ("!allowhttps://a.example.com/\n", 1),
+            ("# c\n\n  !allow  \n", 3),
+            ("!block https://a.example.com/\n", 1),
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let err = bl.load_text_from(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{text:?}");
+            assert!(
+                err.to_string().starts_with(&format!("line {}:", line)),
+                "{text:?}: {err}"
+            );
+        }
+        assert!(bl.is_blocked("https://kept.example.com/x"));
+
+        std::fs::write(
+            &path,
+            "!source  feed \n\\!literal\n!allow\thttps://a.example.com/ok/\n",
+        )
+        .unwrap();
+        bl.load_text_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let loaded = bl.contents();
+        assert_eq!(
+            loaded.block,
+            [(Some("feed".to_string()), "!literal".to_string())]
+        );
+        assert_eq!(loaded.allow, ["https://a.example.com/ok/"]);
+    }
+
+    #[test]
+    fn text_roundtrip_is_sorted() {
+        let bl = DynamicBlockList::from_patterns(["https://b.example.com/"]);
+        bl.extend(["https://a.example.com/"]);
+        bl.allow(["https://b.example.com/ok/"]);
+
+        let path = temp_path("roundtrip.txt");
+        bl.save_text_to(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(
+            lines,
+            [
+                "https://a.example.com/",
+                "https://b.example.com/",
+                "!allow https://b.example.com/ok/",
+            ]
+        );
+
+        let restored = DynamicBlockList::new();
+        restored.load_text_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(restored.is_blocked("https://a.example.com/x"));
+        assert!(!restored.is_blocked("https://b.example.com/ok/x"));
+        assert!(restored.is_blocked("https://b.example.com/x"));
+    }
+}
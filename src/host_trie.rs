@@ -0,0 +1,282 @@
+//! Host-anchored domain matcher.
+//!
+//! Domains are stored label-reversed with a trailing dot
+//! (`criteo.net` → `net.criteo.`) in a regular [`Trie`], so one entry
+//! matches the domain itself and every subdomain under any scheme, while a
+//! lookup on `evilcriteo.net` (`net.evilcriteo.`) does not. Lookups reverse
+//! the host into a stack buffer — no heap allocation for any valid DNS name.
+//!
+//! [`StaticHostTrie`] answers the same lookups over a [`StaticTrie`] of
+//! reversed keys, which is how `build.rs` emits `@host` pattern lists.
+
+use crate::pattern_file::host_key;
+use crate::trie::{StaticTrie, Trie};
+
+/// Hosts up to this many bytes are reversed on the stack. DNS names are
+/// capped at 253, so only malformed inputs take the heap path.
+const STACK_HOST_LEN: usize = 256;
+
+/// Host-suffix trie: an entry like `criteo.net` blocks `criteo.net` and
+/// `*.criteo.net`, regardless of scheme or port.
+#[derive(Debug, Default)]
+pub struct HostTrie {
+    trie: Trie,
+}
+
+impl HostTrie {
+    /// Create an empty host trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a domain. Leading `*.` / `.` and a trailing `.` are ignored and
+    /// ASCII case is folded, so `*.Criteo.net.` and `criteo.net` are the
+    /// same entry.
+    pub fn insert(&mut self, domain: &str) {
+        let domain = normalize(domain);
+        if domain.is_empty() {
+            return;
+        }
+        self.trie.insert(&host_key(domain));
+    }
+
+    /// Check if `host` (e.g. from `url::Url::host_str`) is, or is a
+    /// subdomain of, any stored domain.
+    #[inline]
+    pub fn contains_host(&self, host: &str) -> bool {
+        self.find_host(host).is_some()
+    }
+
+    /// Like [`HostTrie::contains_host`], but returns the matched suffix of
+    /// `host` — the part equal to the stored domain.
+    pub fn find_host<'a>(&self, host: &'a str) -> Option<&'a str> {
+        find_reversed(host, |key| self.trie.find_prefix(key).map(|m| m.len))
+    }
+
+    /// Check a raw URL string by its host. Handles any scheme as well as
+    /// protocol-relative (`//host/path`) URLs without a full parse.
+    #[inline]
+    pub fn contains_url(&self, url: &str) -> bool {
+        match extract_host(url) {
+            Some(host) => self.contains_host(host),
+            None => false,
+        }
+    }
+
+    /// Check an already-parsed URL by its host.
+    #[inline]
+    pub fn contains_parsed(&self, url: &url::Url) -> bool {
+        match url.host_str() {
+            Some(host) => self.contains_host(host),
+            None => false,
+        }
+    }
+
+    /// Number of stored domains.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// `true` if no domain has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Freeze into the packed representation. See [`Trie::shrink_to_fit`].
+    pub fn shrink_to_fit(&mut self) {
+        self.trie.shrink_to_fit();
+    }
+}
+
+/// [`HostTrie`] over packed arrays of reversed keys, as generated by
+/// `build.rs`.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticHostTrie<'a> {
+    trie: StaticTrie<'a>,
+}
+
+impl<'a> StaticHostTrie<'a> {
+    /// Wrap a trie whose keys are label-reversed hosts with a trailing dot
+    /// (see [`host_key`]).
+    pub const fn new(trie: StaticTrie<'a>) -> Self {
+        StaticHostTrie { trie }
+    }
+
+    /// Same as [`HostTrie::contains_host`].
+    #[inline]
+    pub fn contains_host(&self, host: &str) -> bool {
+        self.find_host(host).is_some()
+    }
+
+    /// Same as [`HostTrie::find_host`].
+    pub fn find_host<'h>(&self, host: &'h str) -> Option<&'h str> {
+        find_reversed(host, |key| self.trie.find_prefix(key).map(|m| m.len))
+    }
+
+    /// Same as [`HostTrie::contains_url`].
+    #[inline]
+    pub fn contains_url(&self, url: &str) -> bool {
+        match extract_host(url) {
+            Some(host) => self.contains_host(host),
+            None => false,
+        }
+    }
+
+    /// Number of stored domains.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// `true` if no domain is stored.
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+}
+
+/// Reverse `host` and hand the key to `matched_len`, which returns the byte
+/// length of the stored key that is a prefix of it. Maps that length back
+/// to the matched suffix of `host`.
+#[inline]
+fn find_reversed(host: &str, matched_len: impl Fn(&str) -> Option<usize>) -> Option<&str> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if host.is_empty() {
+        return None;
+    }
+    // Reversed bytes of a `&str` split on ASCII dots stay valid UTF-8.
+    let len = if host.len() < STACK_HOST_LEN {
+        let mut buf = [0u8; STACK_HOST_LEN];
+        let n = reverse_labels_into(host.as_bytes(), &mut buf);
+        matched_len(std::str::from_utf8(&buf[..n]).ok()?)
+    } else {
+        let mut buf = Vec::with_capacity(host.len() + 1);
+        reverse_labels(host.as_bytes(), &mut buf);
+        matched_len(std::str::from_utf8(&buf).ok()?)
+    }?;
+    // `len` includes the trailing dot of the reversed key.
+    Some(&host[host.len() + 1 - len..])
+}
+
+/// Strip wildcard / root-dot decoration from a configured domain.
+fn normalize(domain: &str) -> &str {
+    let domain = domain.trim();
+    let domain = domain.strip_prefix("*.").unwrap_or(domain);
+    let domain = domain.strip_prefix('.').unwrap_or(domain);
+    domain.strip_suffix('.').unwrap_or(domain)
+}
+
+/// Append `host` label-reversed and lowercased, with a trailing dot.
+fn reverse_labels(host: &[u8], out: &mut Vec<u8>) {
+    for label in host.rsplit(|&b| b == b'.') {
+        out.extend(label.iter().map(u8::to_ascii_lowercase));
+        out.push(b'.');
+    }
+}
+
+/// Stack-buffer variant of [`reverse_labels`]. `buf` must hold
+/// `host.len() + 1` bytes. Returns the number of bytes written.
+#[inline]
+fn reverse_labels_into(host: &[u8], buf: &mut [u8]) -> usize {
+    let mut n = 0;
+    for label in host.rsplit(|&b| b == b'.') {
+        for &b in label {
+            buf[n] = b.to_ascii_lowercase();
+            n += 1;
+        }
+        buf[n] = b'.';
+        n += 1;
+    }
+    n
+}
+
+/// Pull the host out of `scheme://[user@]host[:port]/...` or `//host/...`.
+pub(crate) fn extract_host(url: &str) -> Option<&str> {
+    let rest = match url.find("//") {
+        Some(i) if is_scheme_prefix(&url[..i]) => &url[i + 2..],
+        _ => return None,
+    };
+    let authority = match rest.find(['/', '?', '#']) {
+        Some(end) => &rest[..end],
+        None => rest,
+    };
+    let host = match authority.rfind('@') {
+        Some(at) => &authority[at + 1..],
+        None => authority,
+    };
+    if host.starts_with('[') {
+        // IPv6 literal — no domain labels to match.
+        return None;
+    }
+    let host = match host.find(':') {
+        Some(colon) => &host[..colon],
+        None => host,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// `""` (protocol-relative) or `scheme:`.
+fn is_scheme_prefix(s: &str) -> bool {
+    match s.strip_suffix(':') {
+        Some(scheme) => scheme
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')),
+        None => s.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trie(domains: &[&str]) -> HostTrie {
+        let mut t = HostTrie::new();
+        for d in domains {
+            t.insert(d);
+        }
+        t
+    }
+
+    #[test]
+    fn matches_domain_and_subdomains() {
+        let t = trie(&["criteo.net", "hotjar.com"]);
+        assert!(t.contains_host("criteo.net"));
+        assert!(t.contains_host("static.criteo.net"));
+        assert!(t.contains_host("a.b.static.criteo.net"));
+        assert!(t.contains_host("Static.Criteo.NET"));
+        assert!(!t.contains_host("evilcriteo.net"));
+        assert!(!t.contains_host("criteo.network"));
+        assert!(!t.contains_host("net"));
+        assert!(!t.contains_host(""));
+    }
+
+    #[test]
+    fn find_host_returns_matched_suffix() {
+        let t = trie(&["*.criteo.net"]);
+        assert_eq!(t.find_host("static.criteo.net"), Some("criteo.net"));
+        assert_eq!(t.find_host("a.Static.Criteo.net"), Some("Criteo.net"));
+        assert_eq!(t.find_host("criteo.net."), Some("criteo.net"));
+        assert_eq!(t.find_host("example.com"), None);
+    }
+
+    #[test]
+    fn matches_urls_over_any_scheme() {
+        let t = trie(&["criteo.net"]);
+        assert!(t.contains_url("https://static.criteo.net/js/ld/ld.js"));
+        assert!(t.contains_url("http://static.criteo.net/js/ld/ld.js"));
+        assert!(t.contains_url("//static.criteo.net/js/ld/ld.js"));
+        assert!(t.contains_url("wss://user@rt.criteo.net:8443?x=1"));
+        assert!(!t.contains_url("https://example.com/criteo.net/"));
+        assert!(!t.contains_url("static.criteo.net/js"));
+        assert!(!t.contains_url("https://[::1]/"));
+
+        let parsed = url::Url::parse("https://gum.criteo.net/sync").unwrap();
+        assert!(t.contains_parsed(&parsed));
+    }
+
+    #[test]
+    fn works_after_freeze() {
+        let mut t = trie(&["doubleclick.net"]);
+        t.shrink_to_fit();
+        assert!(t.contains_host("googleads.g.doubleclick.net"));
+        assert_eq!(t.len(), 1);
+    }
+}
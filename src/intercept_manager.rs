@@ -1,28 +1,70 @@
 include!(concat!(env!("OUT_DIR"), "/domain_map.rs"));
 
+/// Longest host [`resolve_host`] folds on the stack
+/// (253 bytes plus a trailing dot, rounded up).
+const MAX_HOST_LEN: usize = 256;
+
 impl NetworkInterceptManager {
     /// Establish a new tracker based on the domain.
     pub fn new(url: &Option<Box<url::Url>>) -> NetworkInterceptManager {
-        if let Some(parsed_url) = url {
-            if let Some(domain) = parsed_url.domain() {
-                let mut domain_parts: Vec<&str> = domain.split('.').collect();
-
-                let base = DOMAIN_MAP.get(if domain_parts.len() >= 2 {
-                    domain_parts[domain_parts.len() - 2]
-                } else {
-                    domain
-                });
-                let base = if base.is_none() && domain_parts.len() >= 3 {
-                    domain_parts.pop();
-                    DOMAIN_MAP.get(&domain_parts.join("."))
-                } else {
-                    base
-                };
-
-                return *base.unwrap_or(&NetworkInterceptManager::Unknown);
+        match url.as_deref() {
+            Some(url) => Self::from_url(url),
+            None => NetworkInterceptManager::Unknown,
+        }
+    }
+
+    /// Establish a tracker from an already-parsed URL. Does not allocate.
+    pub fn from_url(url: &url::Url) -> NetworkInterceptManager {
+        match url.domain() {
+            Some(domain) => Self::from_host(domain),
+            None => NetworkInterceptManager::Unknown,
+        }
+    }
+
+    /// Establish a tracker from a bare host such as `www.amazon.com`.
+    /// ASCII case is folded on the stack, so this never allocates.
+    pub fn from_host(host: &str) -> NetworkInterceptManager {
+        resolve_host(host, Self::exact).unwrap_or_default()
+    }
+
+    /// The compiled-in variant keyed on exactly `domain`, if any.
+    #[inline]
+    pub(crate) fn exact(domain: &str) -> Option<NetworkInterceptManager> {
+        DOMAIN_MAP.get(domain).copied()
+    }
+}
+
+/// Look up `host` and each of its parents down to the registrable domain,
+/// returning the first hit, so subdomain-keyed entries (`web.archive.org`)
+/// win over their parent and nothing above the registrable domain (`co.uk`)
+/// matches. `lookup` only ever sees lowercase names.
+pub(crate) fn resolve_host<T>(host: &str, lookup: impl FnMut(&str) -> Option<T>) -> Option<T> {
+    if !host.bytes().any(|b| b.is_ascii_uppercase()) {
+        return resolve_folded(host, lookup);
+    }
+    // DNS names are capped at 253 bytes; anything longer can't be known.
+    let mut buf = [0u8; MAX_HOST_LEN];
+    let buf = buf.get_mut(..host.len())?;
+    buf.copy_from_slice(host.as_bytes());
+    buf.make_ascii_lowercase();
+    // ASCII case folding keeps the bytes valid UTF-8.
+    resolve_folded(std::str::from_utf8(buf).ok()?, lookup)
+}
+
+fn resolve_folded<T>(host: &str, mut lookup: impl FnMut(&str) -> Option<T>) -> Option<T> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    let registrable = crate::public_suffix::registrable_domain(host)?;
+    let mut candidate = host;
+    loop {
+        if let Some(found) = lookup(candidate) {
+            return Some(found);
+        }
+        match candidate.find('.') {
+            Some(dot) if candidate.len() > registrable.len() => {
+                candidate = &candidate[dot + 1..];
             }
+            _ => return None,
         }
-        NetworkInterceptManager::Unknown
     }
 }
 
@@ -91,6 +133,167 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_registrable_domain_keys() {
+        let cases = vec![
+            (
+                "https://web.archive.org/web/2020",
+                NetworkInterceptManager::WebArchive,
+            ),
+            (
+                "https://foo.web.archive.org/",
+                NetworkInterceptManager::WebArchive,
+            ),
+            ("https://www.bbc.com/news", NetworkInterceptManager::Bbc),
+            // Same leading label, different registrable domain.
+            ("https://archive.org/", NetworkInterceptManager::Unknown),
+            (
+                "https://www.bbc.co.uk/news",
+                NetworkInterceptManager::Unknown,
+            ),
+            ("https://x.ai/", NetworkInterceptManager::Unknown),
+            ("https://cnn.example.com/", NetworkInterceptManager::Unknown),
+            ("https://amazon.co.uk/", NetworkInterceptManager::Unknown),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(
+                NetworkInterceptManager::new(&create_url(url)),
+                expected,
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_host_and_url() {
+        let cases = vec![
+            ("www.amazon.com", NetworkInterceptManager::Amazon),
+            ("WWW.Amazon.COM", NetworkInterceptManager::Amazon),
+            ("amazon.com.", NetworkInterceptManager::Amazon),
+            ("Web.Archive.org", NetworkInterceptManager::WebArchive),
+            ("archive.org", NetworkInterceptManager::Unknown),
+            ("127.0.0.1", NetworkInterceptManager::Unknown),
+            ("", NetworkInterceptManager::Unknown),
+        ];
+        for (host, expected) in cases {
+            assert_eq!(NetworkInterceptManager::from_host(host), expected, "{host}");
+        }
+
+        let long = format!("{}.amazon.com", "A".repeat(300));
+        assert_eq!(
+            NetworkInterceptManager::from_host(&long),
+            NetworkInterceptManager::Unknown
+        );
+
+        let url = Url::parse("https://en.Wikipedia.org/wiki/Rust").unwrap();
+        assert_eq!(
+            NetworkInterceptManager::from_url(&url),
+            NetworkInterceptManager::Wikipedia
+        );
+        let ip = Url::parse("http://127.0.0.1/").unwrap();
+        assert_eq!(
+            NetworkInterceptManager::from_url(&ip),
+            NetworkInterceptManager::Unknown
+        );
+    }
+
+    #[test]
+    fn test_intercept_resource_by_type() {
+        use crate::resource_type::ResourceType;
+
+        let tiktok = NetworkInterceptManager::Tiktok;
+        let url = "https://www.tiktok.com/ttwid/check";
+        assert!(tiktok.intercept_resource(url, ResourceType::Script));
+        assert!(tiktok.intercept_resource(url, ResourceType::Other));
+        assert!(!tiktok.intercept_resource(url, ResourceType::Document));
+        assert!(!tiktok.intercept_resource(url, ResourceType::Xhr));
+        assert!(!NetworkInterceptManager::Unknown.intercept_resource(url, ResourceType::Script));
+    }
+
+    #[test]
+    fn test_visual_categories() {
+        use crate::resource_type::ResourceType;
+
+        let amazon = NetworkInterceptManager::Amazon;
+        let image = "https://m.media-amazon.com/images/I/71abc.jpg";
+        assert!(!amazon.intercept_detection(image, false, false));
+        assert!(amazon.intercept_detection(image, true, false));
+        assert!(amazon.intercept_resource(image, ResourceType::Image));
+        assert!(!amazon.intercept_resource(image, ResourceType::Script));
+
+        let tiktok = NetworkInterceptManager::Tiktok;
+        let video = "https://v16-webapp-prime.tiktok.com/video/tos/abc";
+        assert!(tiktok.intercept_detection(video, true, false));
+        assert!(tiktok.intercept_resource(video, ResourceType::Media));
+        assert!(!tiktok.intercept_detection(video, true, true));
+    }
+
+    #[test]
+    fn test_allow_overrides_domain_blocks() {
+        use crate::resource_type::ResourceType;
+
+        let amazon = NetworkInterceptManager::Amazon;
+        let aui = "https://images-na.ssl-images-amazon.com/images/G/01/AUIClients/A.js";
+        assert!(amazon.is_allowed(aui));
+        assert!(!amazon.intercept_resource(aui, ResourceType::Image));
+        assert!(!amazon.intercept_detection(aui, true, false));
+        assert!(!amazon.is_allowed("https://m.media-amazon.com/images/I/71abc.jpg"));
+        assert!(!NetworkInterceptManager::Unknown.is_allowed(aui));
+    }
+
+    #[test]
+    fn test_detection_agrees_with_resource_table() {
+        use crate::pattern_file::{self, MatchMode};
+        use crate::resource_type::ResourceType::*;
+
+        for dir in std::fs::read_dir("url_patterns/domains").unwrap() {
+            let dir = dir.unwrap().path();
+            let name = dir.file_name().unwrap().to_str().unwrap();
+            let manager = NetworkInterceptManager::exact(name).unwrap();
+
+            // A URL hitting each pattern of every category, allow included.
+            let mut urls = Vec::new();
+            for category in std::fs::read_dir(&dir).unwrap() {
+                for file in std::fs::read_dir(category.unwrap().path()).unwrap() {
+                    let text = std::fs::read_to_string(file.unwrap().path()).unwrap();
+                    for entry in pattern_file::parse(&text).unwrap().entries {
+                        urls.push(match entry.mode {
+                            MatchMode::Prefix => entry.pattern,
+                            MatchMode::Host => format!("https://{}/a.js", entry.pattern),
+                            MatchMode::Substring => {
+                                format!("https://example.net/{}", entry.pattern)
+                            }
+                        });
+                    }
+                }
+            }
+
+            for url in &urls {
+                let resource = |t| manager.intercept_resource(url, t);
+                for ignore_visuals in [false, true] {
+                    assert_eq!(
+                        manager.intercept_detection(url, ignore_visuals, true),
+                        resource(Xhr),
+                        "{name}: {url}"
+                    );
+                }
+                assert_eq!(
+                    manager.intercept_detection(url, false, false),
+                    resource(Script),
+                    "{name}: {url}"
+                );
+                assert_eq!(
+                    manager.intercept_detection(url, true, false),
+                    [Script, Stylesheet, Image, Font, Media]
+                        .into_iter()
+                        .any(resource),
+                    "{name}: {url}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_invalid_urls() {
         let cases = vec!["not-a-url", "ftp://invalid.protocol.com", "http://", ""];
@@ -1,12 +1,35 @@
 /// Adblock patterns.
 pub mod adblock;
+/// Unified request blocker over all built-in lists.
+pub mod blocker;
+/// Domain profiles registered at runtime.
+pub mod domain_profiles;
 /// Lock-free dynamic block list for runtime seeding.
 pub mod dynamic_blocklist;
+/// Host-anchored domain trie.
+pub mod host_trie;
 /// interception manager
 pub mod intercept_manager;
+/// Pattern list linter.
+pub mod lint;
+/// Common request matcher trait and combinators.
+pub mod matcher;
+/// Pattern file grammar.
+pub mod pattern_file;
+/// Public-suffix-aware registrable domains.
+pub mod public_suffix;
+/// Request resource types.
+pub mod resource_type;
 /// Scripts to block;
 pub mod scripts;
+/// Multi-pattern substring matcher.
+pub mod substring;
 /// Trie tree.
 pub mod trie;
 /// Xhr block patterns.
 pub mod xhr;
+
+/// The README's examples, compiled and run as doctests.
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
@@ -0,0 +1,544 @@
+//! Lint pattern lists for dead and over-broad entries.
+//!
+//! [`lint`] reports, per [`LintList`]:
+//!
+//! - patterns another pattern of the same list already covers — a shorter
+//!   URL prefix, a parent `@host` or a contained `@substring`;
+//! - exact duplicates within a list;
+//! - patterns repeated in another list;
+//! - unanchored patterns shorter than a threshold, and `@host` patterns
+//!   with a single label, which match far more than they look like.
+//!
+//! Lists come from the compiled-in statics ([`global_tries`],
+//! [`adblock_patterns`]) or from a `url_patterns` tree
+//! ([`load_pattern_dir`]), which keeps file and line for each pattern. The
+//! `pattern_lint` binary runs all of them.
+
+use crate::host_trie::HostTrie;
+use crate::pattern_file::{self, MatchMode};
+use crate::trie::{StaticTrie, Trie};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Unanchored patterns shorter than this are reported by default.
+pub const DEFAULT_MIN_LEN: usize = 12;
+
+/// One pattern and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintPattern {
+    /// The pattern as stored.
+    pub pattern: String,
+    /// How it is matched.
+    pub mode: MatchMode,
+    /// `file:line`, or the list name for compiled-in lists.
+    pub location: String,
+}
+
+/// A named pattern list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintList {
+    /// Name used in reports, e.g. `global/scripts`.
+    pub name: String,
+    /// Patterns in source order.
+    pub patterns: Vec<LintPattern>,
+}
+
+impl LintList {
+    /// Empty list called `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Every pattern of a compiled-in trie. The trie has already merged
+    /// duplicates, so only shadowing and length findings are possible.
+    pub fn from_static_trie(name: impl Into<String>, trie: &StaticTrie<'_>) -> Self {
+        let mut list = Self::new(name);
+        trie.for_each_word(|word| {
+            let pattern = String::from_utf8_lossy(word).into_owned();
+            let location = list.name.clone();
+            list.push(pattern, MatchMode::Prefix, location);
+        });
+        list
+    }
+
+    /// `patterns` in `mode`, located as `name[index]`.
+    pub fn from_patterns<'a>(
+        name: impl Into<String>,
+        mode: MatchMode,
+        patterns: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let mut list = Self::new(name);
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            let location = format!("{}[{}]", list.name, i);
+            list.push(pattern.to_string(), mode, location);
+        }
+        list
+    }
+
+    /// Append one pattern.
+    pub fn push(&mut self, pattern: String, mode: MatchMode, location: String) {
+        self.patterns.push(LintPattern {
+            pattern,
+            mode,
+            location,
+        });
+    }
+}
+
+/// What is wrong with a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// A shorter pattern of the same list matches everything this one does.
+    Shadowed {
+        /// The covering pattern.
+        by: String,
+        /// Where the covering pattern is.
+        location: String,
+    },
+    /// The same pattern appears earlier in the same list.
+    Duplicate {
+        /// Where the first copy is.
+        location: String,
+    },
+    /// The same pattern appears in another list.
+    CrossList {
+        /// The other list.
+        list: String,
+        /// Where the other copy is.
+        location: String,
+    },
+    /// The pattern is too short to be specific.
+    TooShort {
+        /// The threshold it fell under.
+        min_len: usize,
+    },
+}
+
+/// One lint result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// List the pattern belongs to.
+    pub list: String,
+    /// The offending pattern.
+    pub pattern: String,
+    /// Where it is.
+    pub location: String,
+    /// What is wrong with it.
+    pub kind: FindingKind,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?} ", self.location, self.pattern)?;
+        match &self.kind {
+            FindingKind::Shadowed { by, location } => {
+                write!(f, "is shadowed by {:?} at {}", by, location)
+            }
+            FindingKind::Duplicate { location } => write!(f, "duplicates {}", location),
+            FindingKind::CrossList { list, location } => {
+                write!(f, "is also in {} at {}", list, location)
+            }
+            FindingKind::TooShort { min_len } => {
+                write!(f, "is shorter than {} bytes and matches broadly", min_len)
+            }
+        }
+    }
+}
+
+/// Lint `lists`. Findings come list by list, in pattern order.
+///
+/// `min_len` applies to patterns not anchored at a scheme (`https://`,
+/// `//`): bare prefixes such as path lists, and substrings.
+pub fn lint(lists: &[LintList], min_len: usize) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    // First list and location of each (mode, pattern).
+    let mut seen: HashMap<(MatchMode, &str), (&str, &str)> = HashMap::new();
+
+    for list in lists {
+        let mut first: HashMap<(MatchMode, &str), &str> = HashMap::new();
+        let shadows = Shadows::new(list);
+        for entry in &list.patterns {
+            let finding = |kind| Finding {
+                list: list.name.clone(),
+                pattern: entry.pattern.clone(),
+                location: entry.location.clone(),
+                kind,
+            };
+            let key = (entry.mode, entry.pattern.as_str());
+            if let Some(location) = first.get(&key) {
+                findings.push(finding(FindingKind::Duplicate {
+                    location: location.to_string(),
+                }));
+                continue;
+            }
+            first.insert(key, &entry.location);
+
+            match seen.get(&key) {
+                Some((other, location)) if *other != list.name => {
+                    findings.push(finding(FindingKind::CrossList {
+                        list: other.to_string(),
+                        location: location.to_string(),
+                    }))
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(key, (&list.name, &entry.location));
+                }
+            }
+
+            if let Some(by) = shadows.covering(entry) {
+                findings.push(finding(FindingKind::Shadowed {
+                    by: by.pattern.clone(),
+                    location: by.location.clone(),
+                }));
+            }
+
+            if too_short(entry, min_len) {
+                findings.push(finding(FindingKind::TooShort { min_len }));
+            }
+        }
+    }
+    findings
+}
+
+/// Per-mode lookups over one list for [`lint`].
+struct Shadows<'a> {
+    prefixes: Trie,
+    hosts: HostTrie,
+    substrings: Vec<&'a LintPattern>,
+    /// First entry for each (mode, pattern).
+    entries: HashMap<(MatchMode, &'a str), &'a LintPattern>,
+}
+
+impl<'a> Shadows<'a> {
+    fn new(list: &'a LintList) -> Self {
+        let mut shadows = Shadows {
+            prefixes: Trie::new(),
+            hosts: HostTrie::new(),
+            substrings: Vec::new(),
+            entries: HashMap::new(),
+        };
+        for entry in &list.patterns {
+            let key = (entry.mode, entry.pattern.as_str());
+            if shadows.entries.contains_key(&key) || entry.pattern.is_empty() {
+                continue;
+            }
+            shadows.entries.insert(key, entry);
+            match entry.mode {
+                MatchMode::Prefix => shadows.prefixes.insert(&entry.pattern),
+                MatchMode::Host => shadows.hosts.insert(&entry.pattern),
+                MatchMode::Substring => shadows.substrings.push(entry),
+            }
+        }
+        shadows.prefixes.shrink_to_fit();
+        shadows.hosts.shrink_to_fit();
+        shadows
+    }
+
+    /// A strictly shorter pattern in the same mode that covers `entry`.
+    fn covering(&self, entry: &LintPattern) -> Option<&'a LintPattern> {
+        let pattern = entry.pattern.as_str();
+        let by = match entry.mode {
+            MatchMode::Prefix => self.prefixes.find_prefix(pattern)?.pattern,
+            MatchMode::Host => self.hosts.find_host(pattern)?,
+            MatchMode::Substring => {
+                return self
+                    .substrings
+                    .iter()
+                    .filter(|other| other.pattern.len() < pattern.len())
+                    .find(|other| pattern.contains(other.pattern.as_str()))
+                    .copied()
+            }
+        };
+        if by.len() < pattern.len() {
+            self.entries.get(&(entry.mode, by)).copied()
+        } else {
+            None
+        }
+    }
+}
+
+/// Single-label hosts, and unanchored patterns under `min_len` bytes.
+fn too_short(entry: &LintPattern, min_len: usize) -> bool {
+    let pattern = entry.pattern.as_str();
+    match entry.mode {
+        MatchMode::Host => !pattern.contains('.'),
+        MatchMode::Prefix if is_anchored(pattern) => false,
+        _ => pattern.len() < min_len,
+    }
+}
+
+/// `scheme://...` or `//...`.
+fn is_anchored(pattern: &str) -> bool {
+    match pattern.find("//") {
+        Some(0) => true,
+        Some(i) => pattern[..i].strip_suffix(':').is_some_and(|scheme| {
+            !scheme.is_empty()
+                && scheme
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+        }),
+        None => false,
+    }
+}
+
+/// The compiled-in global tries, named after their statics.
+pub fn global_tries() -> Vec<LintList> {
+    use crate::scripts::*;
+    use crate::xhr::*;
+    [
+        ("scripts::URL_IGNORE_TRIE", &URL_IGNORE_TRIE),
+        ("scripts::URL_IGNORE_EMBEDED_TRIE", &URL_IGNORE_EMBEDED_TRIE),
+        (
+            "scripts::URL_IGNORE_SCRIPT_BASE_PATHS",
+            &URL_IGNORE_SCRIPT_BASE_PATHS,
+        ),
+        (
+            "scripts::URL_IGNORE_SCRIPT_STYLES_PATHS",
+            &URL_IGNORE_SCRIPT_STYLES_PATHS,
+        ),
+        ("scripts::URL_IGNORE_CSS", &URL_IGNORE_CSS),
+        ("scripts::URL_IGNORE_TRIE_PATHS", &URL_IGNORE_TRIE_PATHS),
+        ("xhr::URL_IGNORE_XHR_TRIE", &URL_IGNORE_XHR_TRIE),
+        ("xhr::URL_IGNORE_XHR_MEDIA_TRIE", &URL_IGNORE_XHR_MEDIA_TRIE),
+    ]
+    .into_iter()
+    .map(|(name, trie)| LintList::from_static_trie(name, trie))
+    .collect()
+}
+
+/// [`ADBLOCK_PATTERNS`](crate::adblock::ADBLOCK_PATTERNS), matched as
+/// substrings.
+pub fn adblock_patterns() -> LintList {
+    LintList::from_patterns(
+        "adblock::ADBLOCK_PATTERNS",
+        MatchMode::Substring,
+        crate::adblock::ADBLOCK_PATTERNS.iter().copied(),
+    )
+}
+
+/// Every list under a `url_patterns` tree: `global/<category>` and
+/// `domains/<domain>/<category>`, with `!allow` lines moved to
+/// `domains/<domain>/allow`. Expired files are linted too.
+///
+/// Fails with [`io::ErrorKind::InvalidData`] naming the file and line of
+/// the first malformed line.
+pub fn load_pattern_dir(root: impl AsRef<Path>) -> io::Result<Vec<LintList>> {
+    let root = root.as_ref();
+    let mut lists = Vec::new();
+    let global = root.join("global");
+    if global.is_dir() {
+        for category in sorted_dirs(&global)? {
+            let name = format!("global/{}", file_name(&category));
+            let mut list = LintList::new(name);
+            load_category(&category, &mut list, None)?;
+            lists.push(list);
+        }
+    }
+    let domains = root.join("domains");
+    if domains.is_dir() {
+        for domain in sorted_dirs(&domains)? {
+            let prefix = format!("domains/{}", file_name(&domain));
+            let mut allow = LintList::new(format!("{}/allow", prefix));
+            for category in sorted_dirs(&domain)? {
+                let name = file_name(&category);
+                if name == "allow" {
+                    load_category(&category, &mut allow, None)?;
+                    continue;
+                }
+                let mut list = LintList::new(format!("{}/{}", prefix, name));
+                load_category(&category, &mut list, Some(&mut allow))?;
+                lists.push(list);
+            }
+            if !allow.patterns.is_empty() {
+                lists.push(allow);
+            }
+        }
+    }
+    Ok(lists)
+}
+
+/// Parse the files directly under `dir` into `list`, or into `allow` for
+/// `!allow` lines when given.
+fn load_category(
+    dir: &Path,
+    list: &mut LintList,
+    mut allow: Option<&mut LintList>,
+) -> io::Result<()> {
+    let mut files = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    files.retain(|f| f.is_file());
+    files.sort();
+    for file in files {
+        let text = std::fs::read_to_string(&file)?;
+        let parsed = pattern_file::parse(&text).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", file.display(), e),
+            )
+        })?;
+        for entry in parsed.entries {
+            let location = format!("{}:{}", file.display(), entry.line);
+            let target = match allow.as_deref_mut() {
+                Some(allow) if entry.allow => allow,
+                _ => &mut *list,
+            };
+            target.push(entry.pattern, entry.mode, location);
+        }
+    }
+    Ok(())
+}
+
+fn sorted_dirs(dir: &Path) -> io::Result<Vec<std::path::PathBuf>> {
+    let mut dirs = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    dirs.retain(|d| d.is_dir());
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(findings: &[Finding]) -> Vec<(&str, &FindingKind)> {
+        findings
+            .iter()
+            .map(|f| (f.pattern.as_str(), &f.kind))
+            .collect()
+    }
+
+    #[test]
+    fn reports_shadowed_duplicate_and_short() {
+        let scripts = LintList::from_patterns(
+            "scripts",
+            MatchMode::Prefix,
+            [
+                "https://static.criteo.net/js/ld/ld.js",
+                "https://static.criteo.net/",
+                "https://static.criteo.net/",
+                "analytics.",
+            ],
+        );
+        let findings = lint(&[scripts], DEFAULT_MIN_LEN);
+        assert_eq!(
+            kinds(&findings),
+            [
+                (
+                    "https://static.criteo.net/js/ld/ld.js",
+                    &FindingKind::Shadowed {
+                        by: "https://static.criteo.net/".into(),
+                        location: "scripts[1]".into(),
+                    }
+                ),
+                (
+                    "https://static.criteo.net/",
+                    &FindingKind::Duplicate {
+                        location: "scripts[1]".into()
+                    }
+                ),
+                ("analytics.", &FindingKind::TooShort { min_len: 12 }),
+            ]
+        );
+        assert_eq!(
+            findings[1].to_string(),
+            "scripts[2]: \"https://static.criteo.net/\" duplicates scripts[1]"
+        );
+    }
+
+    #[test]
+    fn modes_and_cross_lists() {
+        let mut hosts = LintList::new("hosts");
+        for host in [
+            "criteo.net",
+            "static.criteo.net",
+            "evilcriteo.net",
+            "localhost",
+        ] {
+            hosts.push(host.into(), MatchMode::Host, host.into());
+        }
+        let substrings = LintList::from_patterns(
+            "substrings",
+            MatchMode::Substring,
+            [
+                "/pixel.gif?id=",
+                "googletagmanager.com/gtm.js",
+                "googletagmanager.com",
+            ],
+        );
+        let again =
+            LintList::from_patterns("again", MatchMode::Substring, ["googletagmanager.com"]);
+        // Same text in another mode is a different pattern.
+        let prefixes = LintList::from_patterns("prefixes", MatchMode::Prefix, ["criteo.net/x/y"]);
+        let findings = lint(&[hosts, substrings, again, prefixes], DEFAULT_MIN_LEN);
+        assert_eq!(
+            kinds(&findings),
+            [
+                (
+                    "static.criteo.net",
+                    &FindingKind::Shadowed {
+                        by: "criteo.net".into(),
+                        location: "criteo.net".into(),
+                    }
+                ),
+                ("localhost", &FindingKind::TooShort { min_len: 12 }),
+                (
+                    "googletagmanager.com/gtm.js",
+                    &FindingKind::Shadowed {
+                        by: "googletagmanager.com".into(),
+                        location: "substrings[2]".into(),
+                    }
+                ),
+                (
+                    "googletagmanager.com",
+                    &FindingKind::CrossList {
+                        list: "substrings".into(),
+                        location: "substrings[2]".into(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn anchoring() {
+        assert!(is_anchored("https://t.co/"));
+        assert!(is_anchored("//t.co/"));
+        assert!(!is_anchored("/ads//x"));
+        assert!(!is_anchored("a b://x"));
+    }
+
+    #[test]
+    fn lints_the_repo_lists() {
+        let mut lists =
+            load_pattern_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/url_patterns")).unwrap();
+        assert!(lists.iter().any(|l| l.name == "global/scripts"));
+        assert!(lists.iter().any(|l| l.name == "domains/amazon.com/scripts"));
+        let from_files = lists.iter().find(|l| l.name == "global/paths").unwrap();
+        let from_trie = global_tries()
+            .into_iter()
+            .find(|l| l.name == "scripts::URL_IGNORE_TRIE_PATHS")
+            .unwrap();
+        assert_eq!(from_files.patterns.len(), from_trie.patterns.len());
+        assert!(from_files.patterns[0].location.ends_with("patterns.txt:2"));
+
+        lists.push(adblock_patterns());
+        // Every finding points at a real pattern.
+        for finding in lint(&lists, DEFAULT_MIN_LEN) {
+            assert!(!finding.pattern.is_empty(), "{finding}");
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! One "should this request be blocked" interface for every blocker.
+//!
+//! [`UrlMatcher`] takes a [`RequestContext`] — the request URL, the page
+//! that made it and its resource type — so tries, the dynamic list, the
+//! adblock engine and the per-domain managers can be chained without
+//! special-casing each one. The combinators ([`any_of`], [`all_of`],
+//! [`first_match`], [`allow_then_block`]) are matchers themselves and nest.
+//!
+//! ```rust
+//! use spider_network_blocker::dynamic_blocklist::DynamicBlockList;
+//! use spider_network_blocker::intercept_manager::NetworkInterceptManager;
+//! use spider_network_blocker::matcher::{allow_then_block, first_match, RequestContext, UrlMatcher};
+//! use spider_network_blocker::scripts::URL_IGNORE_TRIE;
+//! use spider_network_blocker::trie::Trie;
+//!
+//! let page_url = url::Url::parse("https://example.com/").ok().map(Box::new);
+//! let dynamic_list = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+//! let mut allow_list = Trie::new();
+//! allow_list.insert("https://www.googletagmanager.com/ns.html");
+//!
+//! let blocker = allow_then_block(
+//!     allow_list,
+//!     first_match([
+//!         Box::new(&URL_IGNORE_TRIE) as Box<dyn UrlMatcher + Send + Sync>,
+//!         Box::new(NetworkInterceptManager::new(&page_url).matcher(true)),
+//!         Box::new(dynamic_list),
+//!     ]),
+//! );
+//!
+//! let request = RequestContext::new("https://www.googletagmanager.com/gtm.js")
+//!     .with_source_url("https://example.com/")
+//!     .with_resource_type("Script"); // or ResourceType::Script
+//! assert!(blocker.matches(&request));
+//! assert!(!blocker.matches(&RequestContext::new("https://www.googletagmanager.com/ns.html?id=1")));
+//! ```
+
+use crate::host_trie::HostTrie;
+use crate::intercept_manager::NetworkInterceptManager;
+use crate::resource_type::ResourceType;
+use crate::substring::SubstringMatcher;
+use crate::trie::PrefixMatcher;
+use std::sync::Arc;
+
+/// A request to decide on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestContext<'a> {
+    /// URL being requested.
+    pub url: &'a str,
+    /// URL of the page or frame making the request; empty if unknown.
+    pub source_url: &'a str,
+    /// What the request loads; [`ResourceType::Other`] if unknown.
+    pub resource_type: ResourceType,
+}
+
+impl<'a> RequestContext<'a> {
+    /// Context for `url` with no source page or resource type.
+    pub fn new(url: &'a str) -> Self {
+        Self {
+            url,
+            ..Self::default()
+        }
+    }
+
+    /// Set the URL of the page making the request.
+    pub fn with_source_url(mut self, source_url: &'a str) -> Self {
+        self.source_url = source_url;
+        self
+    }
+
+    /// Set the resource type, either a [`ResourceType`] or a CDP string
+    /// such as `"Script"`.
+    pub fn with_resource_type(mut self, resource_type: impl Into<ResourceType>) -> Self {
+        self.resource_type = resource_type.into();
+        self
+    }
+
+    /// `true` for `XHR` and `Fetch` requests.
+    #[inline]
+    pub fn is_xhr(&self) -> bool {
+        self.resource_type.is_xhr()
+    }
+}
+
+/// Decides whether a request should be blocked.
+///
+/// Every [`PrefixMatcher`] — tries, the
+/// [`DynamicBlockList`](crate::dynamic_blocklist::DynamicBlockList), and
+/// references, boxes or `Arc`s of them — is a `UrlMatcher` that checks the
+/// request URL. Other matchers are shared as trait objects: `&dyn
+/// UrlMatcher`, `Box<dyn UrlMatcher>` and `Arc<dyn UrlMatcher>` are
+/// matchers too.
+pub trait UrlMatcher {
+    /// `true` if `request` should be blocked.
+    fn matches(&self, request: &RequestContext<'_>) -> bool;
+}
+
+/// Prefix match on the request URL.
+impl<M: PrefixMatcher + ?Sized> UrlMatcher for M {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.contains_prefix(request.url)
+    }
+}
+
+/// Forward [`UrlMatcher`] through pointers to trait objects. Generic
+/// `&M` / `Box<M>` impls would overlap the [`PrefixMatcher`] blanket impl.
+macro_rules! forward_dyn {
+    ($($ptr:ty),* $(,)?) => {
+        $(
+            impl UrlMatcher for $ptr {
+                #[inline]
+                fn matches(&self, request: &RequestContext<'_>) -> bool {
+                    (**self).matches(request)
+                }
+            }
+        )*
+    };
+}
+
+forward_dyn!(
+    &(dyn UrlMatcher + '_),
+    &(dyn UrlMatcher + Send + Sync + '_),
+    Box<dyn UrlMatcher + '_>,
+    Box<dyn UrlMatcher + Send + Sync + '_>,
+    Arc<dyn UrlMatcher + '_>,
+    Arc<dyn UrlMatcher + Send + Sync + '_>,
+);
+
+/// Host or subdomain match on the request URL.
+impl UrlMatcher for HostTrie {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.contains_url(request.url)
+    }
+}
+
+/// Substring match anywhere in the request URL.
+impl UrlMatcher for SubstringMatcher {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.is_match(request.url)
+    }
+}
+
+/// The domain's pattern set for the request's resource type. Visual
+/// resources only face the script list; see
+/// [`NetworkInterceptManager::matcher`].
+impl UrlMatcher for NetworkInterceptManager {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.matcher(false).matches(request)
+    }
+}
+
+/// A [`NetworkInterceptManager`] with its `ignore_visuals` setting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterceptMatcher {
+    /// The per-domain blocker.
+    pub manager: NetworkInterceptManager,
+    /// Check visual resources (styles, images, fonts, media) against their
+    /// own lists rather than the script list.
+    pub ignore_visuals: bool,
+}
+
+/// A [`NetworkInterceptManager`]'s `allow` list, for the allow side of
+/// [`allow_then_block`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainAllowList(pub NetworkInterceptManager);
+
+impl NetworkInterceptManager {
+    /// This manager as a [`UrlMatcher`] that checks visual resources
+    /// against their own lists when `ignore_visuals` is set, and against
+    /// the script list otherwise.
+    pub fn matcher(self, ignore_visuals: bool) -> InterceptMatcher {
+        InterceptMatcher {
+            manager: self,
+            ignore_visuals,
+        }
+    }
+
+    /// This domain's `allow` list as a [`UrlMatcher`], to put in front of
+    /// global tries with [`allow_then_block`].
+    pub fn allow_list(self) -> DomainAllowList {
+        DomainAllowList(self)
+    }
+}
+
+impl UrlMatcher for DomainAllowList {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.0.is_allowed(request.url)
+    }
+}
+
+impl UrlMatcher for InterceptMatcher {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        let resource_type = request.resource_type.domain_list_type(self.ignore_visuals);
+        self.manager.intercept_resource(request.url, resource_type)
+    }
+}
+
+#[cfg(feature = "adblock")]
+impl UrlMatcher for crate::adblock::engine::AdblockEngine {
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.should_block_resource(request.url, request.source_url, request.resource_type)
+    }
+}
+
+/// Blocks when any matcher does. See [`any_of`].
+#[derive(Debug, Clone)]
+pub struct AnyOf<M> {
+    matchers: Vec<M>,
+}
+
+/// Block when any of `matchers` does, checked in order. Empty never blocks.
+///
+/// Mix matcher types by boxing them:
+///
+/// ```rust
+/// use spider_network_blocker::dynamic_blocklist::DynamicBlockList;
+/// use spider_network_blocker::matcher::{any_of, RequestContext, UrlMatcher};
+/// use spider_network_blocker::scripts::URL_IGNORE_TRIE;
+///
+/// let dynamic_list = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+/// let blocker = any_of([
+///     Box::new(&URL_IGNORE_TRIE) as Box<dyn UrlMatcher + Send + Sync>,
+///     Box::new(dynamic_list),
+/// ]);
+/// assert!(blocker.matches(&RequestContext::new("https://ads.example.com/a.js")));
+/// ```
+pub fn any_of<M: UrlMatcher>(matchers: impl IntoIterator<Item = M>) -> AnyOf<M> {
+    AnyOf {
+        matchers: matchers.into_iter().collect(),
+    }
+}
+
+impl<M: UrlMatcher> UrlMatcher for AnyOf<M> {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.matchers.iter().any(|m| m.matches(request))
+    }
+}
+
+/// Blocks when every matcher does. See [`all_of`].
+#[derive(Debug, Clone)]
+pub struct AllOf<M> {
+    matchers: Vec<M>,
+}
+
+/// Block only when all of `matchers` do. Empty never blocks — an empty
+/// conjunction blocking everything is never what a caller means.
+pub fn all_of<M: UrlMatcher>(matchers: impl IntoIterator<Item = M>) -> AllOf<M> {
+    AllOf {
+        matchers: matchers.into_iter().collect(),
+    }
+}
+
+impl<M: UrlMatcher> UrlMatcher for AllOf<M> {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        !self.matchers.is_empty() && self.matchers.iter().all(|m| m.matches(request))
+    }
+}
+
+/// Ordered matchers that report which one decided. See [`first_match`].
+#[derive(Debug, Clone)]
+pub struct FirstMatch<M> {
+    matchers: Vec<M>,
+}
+
+/// Check `matchers` in order and stop at the first that blocks.
+///
+/// Blocks exactly like [`any_of`]; [`FirstMatch::position`] also tells the
+/// caller which matcher fired, e.g. for logging.
+pub fn first_match<M: UrlMatcher>(matchers: impl IntoIterator<Item = M>) -> FirstMatch<M> {
+    FirstMatch {
+        matchers: matchers.into_iter().collect(),
+    }
+}
+
+impl<M: UrlMatcher> FirstMatch<M> {
+    /// Index of the first matcher that blocks `request`.
+    #[inline]
+    pub fn position(&self, request: &RequestContext<'_>) -> Option<usize> {
+        self.matchers.iter().position(|m| m.matches(request))
+    }
+
+    /// The first matcher that blocks `request`.
+    pub fn find(&self, request: &RequestContext<'_>) -> Option<&M> {
+        self.position(request).map(|i| &self.matchers[i])
+    }
+}
+
+impl<M: UrlMatcher> UrlMatcher for FirstMatch<M> {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        self.position(request).is_some()
+    }
+}
+
+/// An allow list in front of a block list. See [`allow_then_block`].
+#[derive(Debug, Clone)]
+pub struct AllowThenBlock<A, B> {
+    allow: A,
+    block: B,
+}
+
+/// Block what `block` matches unless `allow` matches first.
+pub fn allow_then_block<A: UrlMatcher, B: UrlMatcher>(allow: A, block: B) -> AllowThenBlock<A, B> {
+    AllowThenBlock { allow, block }
+}
+
+impl<A: UrlMatcher, B: UrlMatcher> UrlMatcher for AllowThenBlock<A, B> {
+    #[inline]
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        !self.allow.matches(request) && self.block.matches(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic_blocklist::DynamicBlockList;
+    use crate::scripts::{URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_TRIE};
+    use crate::trie::Trie;
+
+    type Dyn = Box<dyn UrlMatcher + Send + Sync>;
+
+    fn trie(patterns: &[&str]) -> Trie {
+        let mut t = Trie::new();
+        for p in patterns {
+            t.insert(p);
+        }
+        t
+    }
+
+    #[test]
+    fn builtin_matchers() {
+        let req = RequestContext::new("https://www.googletagmanager.com/gtm.js");
+        assert!(URL_IGNORE_TRIE.matches(&req));
+        assert!(!URL_IGNORE_EMBEDED_TRIE.matches(&RequestContext::new("https://example.com/")));
+
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        assert!(bl.matches(&RequestContext::new("https://ads.example.com/a.js")));
+
+        let mut hosts = HostTrie::new();
+        hosts.insert("criteo.net");
+        assert!(hosts.matches(&RequestContext::new("https://static.criteo.net/js/ld.js")));
+
+        let sub = SubstringMatcher::new(["analytics.js"]);
+        assert!(sub.matches(&RequestContext::new("https://example.com/analytics.js")));
+    }
+
+    #[test]
+    fn intercept_manager_uses_resource_type() {
+        let manager = NetworkInterceptManager::Wikipedia;
+        let req = RequestContext::new("https://example.com/app.js").with_resource_type("Script");
+        assert!(!manager.matches(&req));
+        assert!(!manager.matcher(true).matches(&req));
+        assert!(RequestContext::new("").with_resource_type("xhr").is_xhr());
+        assert!(RequestContext::new("").with_resource_type("Fetch").is_xhr());
+        assert!(!RequestContext::new("")
+            .with_resource_type("Script")
+            .is_xhr());
+    }
+
+    #[test]
+    fn intercept_matcher_falls_back_to_scripts() {
+        // CNN has script and style lists but no image list.
+        let cnn = NetworkInterceptManager::Cnn;
+        let script_url = "https://simage2.pubmatic.com/AdServer/pixel";
+        let style_url = "https://libs.outbrain.com/video/player.css";
+        let image = RequestContext::new(script_url).with_resource_type(ResourceType::Image);
+        let style = RequestContext::new(script_url).with_resource_type(ResourceType::Stylesheet);
+        let styled = RequestContext::new(style_url).with_resource_type(ResourceType::Stylesheet);
+
+        // Without ignore_visuals, visual requests face the script list only.
+        assert!(cnn.matches(&image));
+        assert!(cnn.matcher(false).matches(&style));
+        assert!(!cnn.matcher(false).matches(&styled));
+
+        // With it, they face their own list, or the scripts if there is none.
+        assert!(cnn.matcher(true).matches(&image));
+        assert!(!cnn.matcher(true).matches(&style));
+        assert!(cnn.matcher(true).matches(&styled));
+
+        // XHR never falls back.
+        let xhr = RequestContext::new(script_url).with_resource_type(ResourceType::Xhr);
+        assert!(!cnn.matcher(true).matches(&xhr));
+    }
+
+    #[test]
+    fn combinators() {
+        let ads = trie(&["https://ads.example.com/"]);
+        let track = trie(&["https://track.example.com/"]);
+        let ads_req = RequestContext::new("https://ads.example.com/a.js");
+        let track_req = RequestContext::new("https://track.example.com/p");
+        let other = RequestContext::new("https://example.com/");
+
+        let any = any_of([&ads, &track]);
+        assert!(any.matches(&ads_req) && any.matches(&track_req));
+        assert!(!any.matches(&other));
+        assert!(!any_of(Vec::<Trie>::new()).matches(&ads_req));
+
+        let ads_prefix = trie(&["https://ads."]);
+        let both = all_of([&ads, &ads_prefix]);
+        assert!(both.matches(&ads_req));
+        assert!(!all_of([&ads, &track]).matches(&ads_req));
+        assert!(!all_of(Vec::<Trie>::new()).matches(&ads_req));
+
+        let first = first_match([&ads, &track]);
+        assert_eq!(first.position(&track_req), Some(1));
+        assert_eq!(first.position(&other), None);
+        assert!(first.matches(&ads_req));
+
+        let allowed = trie(&["https://ads.example.com/ok/"]);
+        let chain = allow_then_block(&allowed, any_of([&ads, &track]));
+        assert!(chain.matches(&ads_req));
+        assert!(!chain.matches(&RequestContext::new("https://ads.example.com/ok/x")));
+    }
+
+    #[test]
+    fn domain_allow_list_fronts_global_tries() {
+        let aui = RequestContext::new(
+            "https://images-na.ssl-images-amazon.com/images/G/01/AUIClients/A.js",
+        );
+        let global = trie(&["https://images-na.ssl-images-amazon.com/"]);
+        assert!(global.matches(&aui));
+        let chain = allow_then_block(NetworkInterceptManager::Amazon.allow_list(), &global);
+        assert!(!chain.matches(&aui));
+        let chain = allow_then_block(NetworkInterceptManager::Unknown.allow_list(), &global);
+        assert!(chain.matches(&aui));
+    }
+
+    #[test]
+    fn mixed_types_nest() {
+        let bl = DynamicBlockList::from_patterns(["https://ads.example.com/"]);
+        let chain = allow_then_block(
+            trie(&["https://www.googletagmanager.com/ns.html"]),
+            first_match([
+                Box::new(&URL_IGNORE_TRIE) as Dyn,
+                Box::new(bl),
+                Box::new(NetworkInterceptManager::Unknown),
+            ]),
+        );
+        assert!(chain.matches(&RequestContext::new("https://ads.example.com/x")));
+        assert!(chain.matches(&RequestContext::new(
+            "https://www.googletagmanager.com/gtm.js"
+        )));
+        assert!(!chain.matches(&RequestContext::new(
+            "https://www.googletagmanager.com/ns.html?id=1"
+        )));
+        assert!(!chain.matches(&RequestContext::new("https://example.com/")));
+    }
+
+    #[cfg(feature = "adblock")]
+    #[test]
+    fn adblock_engine_gets_full_context() {
+        use crate::adblock::engine::AdblockEngine;
+
+        let engine = AdblockEngine::from_rules(["||ads.example.com^$script"], false);
+        let req = RequestContext::new("https://ads.example.com/a.js")
+            .with_source_url("https://example.com/")
+            .with_resource_type("Script");
+        assert!(engine.matches(&req));
+        assert!(!engine.matches(&req.with_resource_type("Image")));
+    }
+}
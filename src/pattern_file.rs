@@ -0,0 +1,362 @@
+//! The `url_patterns/**/*.txt` file format.
+//!
+//! ```text
+//! # Comments run to the end of the line; blank lines are ignored.
+//! expires: 2027-01-31          optional, before the first pattern
+//!
+//! https://ads.example.com/     URL prefix (the default mode)
+//! @host                        following patterns are hosts
+//! criteo.net                   criteo.net and every subdomain
+//! @substring                   following patterns match anywhere
+//! /pixel.gif?
+//! @prefix                      back to URL prefixes
+//! !allow https://ads.example.com/ok/
+//! ```
+//!
+//! `!allow` puts one pattern, in the current mode, on the domain's allow
+//! list. A file's patterns stop matching once its `expires:` date has
+//! passed: the built-in lists carry the date into the generated tables and
+//! check it at lookup, runtime loaders skip the file.
+//!
+//! This module only depends on `std`: `build.rs` compiles it too, so the
+//! built-in lists and runtime loaders share one parser.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directive switching to URL-prefix patterns.
+const PREFIX_DIRECTIVE: &str = "@prefix";
+/// Directive switching to host patterns.
+const HOST_DIRECTIVE: &str = "@host";
+/// Directive switching to substring patterns.
+const SUBSTRING_DIRECTIVE: &str = "@substring";
+/// Prefix of an allow line.
+const ALLOW_DIRECTIVE: &str = "!allow";
+/// Header naming the last day the file applies.
+const EXPIRES_HEADER: &str = "expires:";
+
+/// How a pattern is compared with a request URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// The URL starts with the pattern.
+    #[default]
+    Prefix,
+    /// The URL's host is the pattern or one of its subdomains.
+    Host,
+    /// The pattern occurs anywhere in the URL.
+    Substring,
+}
+
+impl MatchMode {
+    /// The directive selecting this mode, e.g. `"@host"`.
+    pub fn directive(self) -> &'static str {
+        match self {
+            MatchMode::Prefix => PREFIX_DIRECTIVE,
+            MatchMode::Host => HOST_DIRECTIVE,
+            MatchMode::Substring => SUBSTRING_DIRECTIVE,
+        }
+    }
+}
+
+/// One pattern line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternEntry {
+    /// The pattern, trimmed. Host patterns are lowercased and stripped of a
+    /// leading `*.` / `.` and a trailing `.`.
+    pub pattern: String,
+    /// Mode in effect on this line.
+    pub mode: MatchMode,
+    /// `true` for `!allow` lines.
+    pub allow: bool,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+/// A calendar date from an `expires:` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// Year, e.g. `2027`.
+    pub year: u16,
+    /// Month, `1..=12`.
+    pub month: u8,
+    /// Day of the month, `1..=31`.
+    pub day: u8,
+}
+
+impl Date {
+    /// Parse `YYYY-MM-DD`.
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.split('-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        // Digits only: `str::parse` would also take a sign (`+1`).
+        if !s.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+            return None;
+        }
+        let date = Date {
+            year: year.parse().ok()?,
+            month: month.parse().ok()?,
+            day: day.parse().ok()?,
+        };
+        let leap = date.year % 4 == 0 && (date.year % 100 != 0 || date.year % 400 == 0);
+        let days_in_month = match date.month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month).contains(&date.day).then_some(date)
+    }
+
+    /// Days from 1970-01-01 to this date.
+    fn days_since_epoch(self) -> i64 {
+        // Howard Hinnant's `days_from_civil`.
+        let (m, d) = (i64::from(self.month), i64::from(self.day));
+        let y = i64::from(self.year) - i64::from(m <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Unix time, in seconds, at which this day ends, UTC.
+    pub fn end_secs(self) -> u64 {
+        ((self.days_since_epoch() + 1) * 86_400).max(0) as u64
+    }
+
+    /// `true` once `now` is past the end of this day, UTC.
+    pub fn has_passed(self, now: SystemTime) -> bool {
+        let secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        secs >= self.end_secs()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A parsed pattern file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternFile {
+    /// Last day the file applies, from the `expires:` header.
+    pub expires: Option<Date>,
+    /// Pattern lines in file order.
+    pub entries: Vec<PatternEntry>,
+}
+
+impl PatternFile {
+    /// `true` if the file has an `expires:` date that `now` is past.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|date| date.has_passed(now))
+    }
+}
+
+/// A malformed line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a pattern file. Stops at the first malformed line.
+pub fn parse(text: &str) -> Result<PatternFile, ParseError> {
+    let mut file = PatternFile::default();
+    let mut mode = MatchMode::Prefix;
+    // Set once anything but a comment has been seen.
+    let mut body = false;
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let err = |message: String| ParseError { line, message };
+        let text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        if let Some(date) = text.strip_prefix(EXPIRES_HEADER) {
+            if body {
+                return Err(err(format!(
+                    "`{}` must come before the first pattern",
+                    EXPIRES_HEADER
+                )));
+            }
+            if file.expires.is_some() {
+                return Err(err(format!("duplicate `{}` header", EXPIRES_HEADER)));
+            }
+            let date = date.trim();
+            match Date::parse(date) {
+                Some(date) => file.expires = Some(date),
+                None => return Err(err(format!("invalid date {:?}, expected YYYY-MM-DD", date))),
+            }
+            continue;
+        }
+        body = true;
+
+        if text.starts_with('@') {
+            mode = match text {
+                PREFIX_DIRECTIVE => MatchMode::Prefix,
+                HOST_DIRECTIVE => MatchMode::Host,
+                SUBSTRING_DIRECTIVE => MatchMode::Substring,
+                _ => return Err(err(format!("unknown directive {:?}", text))),
+            };
+            continue;
+        }
+
+        let (allow, pattern) = if text.starts_with('!') {
+            match text.strip_prefix(ALLOW_DIRECTIVE) {
+                Some(rest) if rest.starts_with(char::is_whitespace) => (true, rest.trim()),
+                Some("") => return Err(err(format!("`{}` needs a pattern", ALLOW_DIRECTIVE))),
+                _ => return Err(err(format!("unknown directive {:?}", text))),
+            }
+        } else {
+            (false, text)
+        };
+
+        if pattern.contains(char::is_whitespace) {
+            return Err(err(format!("pattern {:?} contains whitespace", pattern)));
+        }
+        let pattern = match mode {
+            MatchMode::Host => host_pattern(pattern).map_err(err)?,
+            _ => pattern.to_string(),
+        };
+        file.entries.push(PatternEntry {
+            pattern,
+            mode,
+            allow,
+            line,
+        });
+    }
+    Ok(file)
+}
+
+/// Validate and normalize an `@host` pattern.
+fn host_pattern(pattern: &str) -> Result<String, String> {
+    let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+    let host = host.strip_prefix('.').unwrap_or(host);
+    let host = host.strip_suffix('.').unwrap_or(host);
+    let valid = !host.is_empty()
+        && host.split('.').all(|label| !label.is_empty())
+        && host
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_'));
+    if valid {
+        Ok(host.to_ascii_lowercase())
+    } else {
+        Err(format!("invalid host {:?}", pattern))
+    }
+}
+
+/// `criteo.net` → `net.criteo.`: the label-reversed, lowercased key a
+/// [`HostTrie`](crate::host_trie::HostTrie) stores for a host.
+pub fn host_key(host: &str) -> String {
+    let mut key = String::with_capacity(host.len() + 1);
+    for label in host.rsplit('.') {
+        key.extend(label.chars().map(|c| c.to_ascii_lowercase()));
+        key.push('.');
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modes_allow_and_header() {
+        let file = parse(
+            "# header comment\n\
+             expires: 2027-01-31\n\
+             \n\
+             https://ads.example.com/\n\
+             @host\n\
+             *.Criteo.net.\n\
+             !allow ok.criteo.net\n\
+             @substring\n\
+             /pixel.gif?\n\
+             @prefix\n\
+             !allow   https://ads.example.com/ok/\n",
+        )
+        .unwrap();
+        assert_eq!(
+            file.expires,
+            Some(Date {
+                year: 2027,
+                month: 1,
+                day: 31
+            })
+        );
+        let got: Vec<_> = file
+            .entries
+            .iter()
+            .map(|e| (e.pattern.as_str(), e.mode, e.allow, e.line))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("https://ads.example.com/", MatchMode::Prefix, false, 4),
+                ("criteo.net", MatchMode::Host, false, 6),
+                ("ok.criteo.net", MatchMode::Host, true, 7),
+                ("/pixel.gif?", MatchMode::Substring, false, 9),
+                ("https://ads.example.com/ok/", MatchMode::Prefix, true, 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for (text, line, needle) in [
+            ("@regex", 1, "unknown directive"),
+            ("a\n!block b", 2, "unknown directive"),
+            ("!allow", 1, "needs a pattern"),
+            ("!allowx", 1, "unknown directive"),
+            ("https://a.com/ b", 1, "whitespace"),
+            ("@host\nhttps://a.com/", 2, "invalid host"),
+            ("@host\na..com", 2, "invalid host"),
+            ("a\nexpires: 2027-01-01", 2, "before the first pattern"),
+            ("expires: 2027-01-01\nexpires: 2027-01-02", 2, "duplicate"),
+            ("expires: 2027-02-30", 1, "invalid date"),
+            ("expires: tomorrow", 1, "invalid date"),
+            ("expires: 2026-+1-01", 1, "invalid date"),
+            ("expires: +026-01-01", 1, "invalid date"),
+        ] {
+            let err = parse(text).unwrap_err();
+            assert_eq!(err.line, line, "{text:?}");
+            assert!(err.message.contains(needle), "{text:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn expiry_is_end_of_day_utc() {
+        let date = Date::parse("2024-02-29").unwrap();
+        assert_eq!(date.to_string(), "2024-02-29");
+        // 2024-02-29T23:59:59Z and 2024-03-01T00:00:00Z.
+        let last = UNIX_EPOCH + std::time::Duration::from_secs(1_709_251_199);
+        assert!(!date.has_passed(last));
+        assert!(date.has_passed(last + std::time::Duration::from_secs(1)));
+        assert_eq!(date.end_secs(), 1_709_251_200);
+        assert_eq!(Date::parse("2023-02-29"), None);
+        assert_eq!(Date::parse("1970-01-01").unwrap().days_since_epoch(), 0);
+    }
+
+    #[test]
+    fn host_keys_are_label_reversed() {
+        assert_eq!(host_key("Static.Criteo.net"), "net.criteo.static.");
+        assert_eq!(host_key("localhost"), "localhost.");
+    }
+}
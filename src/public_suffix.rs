@@ -0,0 +1,96 @@
+//! Registrable-domain resolution against the Public Suffix List.
+//!
+//! `url_patterns/public_suffix_list.dat` is compiled into phf sets by
+//! `build.rs`, so there is nothing to load at startup. Lookups slice the
+//! host in place and never allocate. Hosts are expected in the lowercase,
+//! punycode form `url::Url::host_str` produces.
+//!
+//! The list is distributed under the Mozilla Public License, v. 2.0; see
+//! `LICENSE-MPL-2.0`.
+
+include!(concat!(env!("OUT_DIR"), "/public_suffix.rs"));
+
+/// The public suffix of `host`: `co.uk` for `www.bbc.co.uk`,
+/// `github.io` for `user.github.io`. A host matching no rule falls back to
+/// its last label, per the list's implicit `*` rule.
+pub fn public_suffix(host: &str) -> &str {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    let mut candidate = host;
+    loop {
+        let parent = candidate.find('.').map(|i| &candidate[i + 1..]);
+        if EXCEPTIONS.contains(candidate) {
+            // `!www.ck` carves `www.ck` out of `*.ck`: the suffix is `ck`.
+            return parent.unwrap_or(candidate);
+        }
+        if RULES.contains(candidate) || parent.is_some_and(|p| WILDCARDS.contains(p)) {
+            return candidate;
+        }
+        match parent {
+            Some(parent) => candidate = parent,
+            None => return candidate,
+        }
+    }
+}
+
+/// The registrable domain of `host` — its public suffix plus one label
+/// (`bbc.co.uk` for `www.bbc.co.uk`). `None` if `host` is itself a public
+/// suffix, or empty.
+pub fn registrable_domain(host: &str) -> Option<&str> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    let suffix = public_suffix(host);
+    if suffix.len() >= host.len() {
+        return None;
+    }
+    let head = &host[..host.len() - suffix.len() - 1];
+    let start = head.rfind('.').map_or(0, |i| i + 1);
+    Some(&host[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_multi_label_suffixes() {
+        assert_eq!(public_suffix("www.bbc.co.uk"), "co.uk");
+        assert_eq!(registrable_domain("www.bbc.co.uk"), Some("bbc.co.uk"));
+        assert_eq!(
+            registrable_domain("en.wikipedia.org"),
+            Some("wikipedia.org")
+        );
+        assert_eq!(
+            registrable_domain("foo.web.archive.org"),
+            Some("archive.org")
+        );
+        assert_eq!(registrable_domain("example.com."), Some("example.com"));
+        assert_eq!(registrable_domain("co.uk"), None);
+        assert_eq!(registrable_domain(""), None);
+    }
+
+    #[test]
+    fn private_wildcard_and_exception_rules() {
+        assert_eq!(registrable_domain("user.github.io"), Some("user.github.io"));
+        // `*.ck` makes every second-level label public ...
+        assert_eq!(registrable_domain("a.b.ck"), Some("a.b.ck"));
+        assert_eq!(registrable_domain("b.ck"), None);
+        // ... except `!www.ck`.
+        assert_eq!(registrable_domain("www.ck"), Some("www.ck"));
+        assert_eq!(registrable_domain("a.www.ck"), Some("www.ck"));
+    }
+
+    #[test]
+    fn unknown_tld_uses_default_rule() {
+        assert_eq!(public_suffix("foo.bar.notatld"), "notatld");
+        assert_eq!(registrable_domain("foo.bar.notatld"), Some("bar.notatld"));
+        assert_eq!(registrable_domain("localhost"), None);
+    }
+
+    #[test]
+    fn punycode_rules() {
+        // 公司.cn
+        assert_eq!(
+            registrable_domain("www.example.xn--55qx5d.cn"),
+            Some("example.xn--55qx5d.cn")
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Request resource types.
+//!
+//! Mirrors Chrome DevTools Protocol `Network.ResourceType`, folding the rarer
+//! kinds into the nearest category a block list cares about.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// What kind of resource a request loads.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum ResourceType {
+    /// Top-level document.
+    Document,
+    /// Frame or iframe document.
+    SubDocument,
+    /// JavaScript.
+    Script,
+    /// CSS.
+    Stylesheet,
+    /// Image.
+    Image,
+    /// Web font.
+    Font,
+    /// Audio, video or text track.
+    Media,
+    /// `XMLHttpRequest`.
+    Xhr,
+    /// `fetch()`.
+    Fetch,
+    /// WebSocket handshake.
+    WebSocket,
+    /// Server-sent events stream.
+    EventSource,
+    /// `navigator.sendBeacon`, `<a ping>` and CSP reports.
+    Ping,
+    /// Anything else, or unknown.
+    #[default]
+    Other,
+}
+
+impl ResourceType {
+    /// Parse a CDP resource type (`"Script"`, `"XHR"`, `"TextTrack"`, …).
+    /// Case-insensitive; also accepts the lowercase adblock spellings
+    /// (`"xmlhttprequest"`, `"sub_frame"`, `"beacon"`). Unknown strings map
+    /// to [`ResourceType::Other`].
+    pub fn from_cdp(s: &str) -> Self {
+        const NAMES: &[(&str, ResourceType)] = &[
+            ("Document", ResourceType::Document),
+            ("main_frame", ResourceType::Document),
+            ("SubDocument", ResourceType::SubDocument),
+            ("sub_frame", ResourceType::SubDocument),
+            ("Script", ResourceType::Script),
+            ("Stylesheet", ResourceType::Stylesheet),
+            ("Image", ResourceType::Image),
+            ("Font", ResourceType::Font),
+            ("Media", ResourceType::Media),
+            ("TextTrack", ResourceType::Media),
+            ("XHR", ResourceType::Xhr),
+            ("xmlhttprequest", ResourceType::Xhr),
+            ("Fetch", ResourceType::Fetch),
+            ("WebSocket", ResourceType::WebSocket),
+            ("EventSource", ResourceType::EventSource),
+            ("Ping", ResourceType::Ping),
+            ("Beacon", ResourceType::Ping),
+            ("CSPViolationReport", ResourceType::Ping),
+        ];
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map_or(ResourceType::Other, |&(_, t)| t)
+    }
+
+    /// CDP spelling. `SubDocument` has no CDP equivalent and keeps its name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResourceType::Document => "Document",
+            ResourceType::SubDocument => "SubDocument",
+            ResourceType::Script => "Script",
+            ResourceType::Stylesheet => "Stylesheet",
+            ResourceType::Image => "Image",
+            ResourceType::Font => "Font",
+            ResourceType::Media => "Media",
+            ResourceType::Xhr => "XHR",
+            ResourceType::Fetch => "Fetch",
+            ResourceType::WebSocket => "WebSocket",
+            ResourceType::EventSource => "EventSource",
+            ResourceType::Ping => "Ping",
+            ResourceType::Other => "Other",
+        }
+    }
+
+    /// Request type as the `adblock` crate spells it.
+    pub fn as_adblock_str(self) -> &'static str {
+        match self {
+            ResourceType::Document => "document",
+            ResourceType::SubDocument => "subdocument",
+            ResourceType::Script => "script",
+            ResourceType::Stylesheet => "stylesheet",
+            ResourceType::Image => "image",
+            ResourceType::Font => "font",
+            ResourceType::Media => "media",
+            ResourceType::Xhr | ResourceType::Fetch => "xmlhttprequest",
+            ResourceType::WebSocket => "websocket",
+            ResourceType::Ping => "ping",
+            ResourceType::EventSource | ResourceType::Other => "other",
+        }
+    }
+
+    /// `true` for styles, images, fonts and media — what `ignore_visuals`
+    /// drops.
+    #[inline]
+    pub fn is_visual(self) -> bool {
+        matches!(
+            self,
+            ResourceType::Stylesheet
+                | ResourceType::Image
+                | ResourceType::Font
+                | ResourceType::Media
+        )
+    }
+
+    /// The type whose domain list a request of this type is checked
+    /// against: visual types face the script list unless `ignore_visuals`
+    /// is set, like every non-XHR request in `intercept_detection`.
+    #[inline]
+    pub fn domain_list_type(self, ignore_visuals: bool) -> ResourceType {
+        if self.is_visual() && !ignore_visuals {
+            ResourceType::Script
+        } else {
+            self
+        }
+    }
+
+    /// The types whose domain lists `intercept_detection` checks a request
+    /// against: XHR requests face the XHR list, everything else the script
+    /// list, plus the visual lists when `ignore_visuals` is set.
+    pub(crate) fn detection_types(ignore_visuals: bool, is_xhr: bool) -> &'static [ResourceType] {
+        if is_xhr {
+            &[ResourceType::Xhr]
+        } else if ignore_visuals {
+            &[
+                ResourceType::Script,
+                ResourceType::Stylesheet,
+                ResourceType::Image,
+                ResourceType::Font,
+                ResourceType::Media,
+            ]
+        } else {
+            &[ResourceType::Script]
+        }
+    }
+
+    /// `true` for `XHR` and `Fetch`.
+    #[inline]
+    pub fn is_xhr(self) -> bool {
+        matches!(self, ResourceType::Xhr | ResourceType::Fetch)
+    }
+}
+
+impl From<&str> for ResourceType {
+    fn from(s: &str) -> Self {
+        Self::from_cdp(s)
+    }
+}
+
+impl FromStr for ResourceType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_cdp(s))
+    }
+}
+
+impl fmt::Display for ResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cdp_names() {
+        assert_eq!(ResourceType::from_cdp("Script"), ResourceType::Script);
+        assert_eq!(ResourceType::from_cdp("XHR"), ResourceType::Xhr);
+        assert_eq!(ResourceType::from_cdp("xhr"), ResourceType::Xhr);
+        assert_eq!(ResourceType::from_cdp("TextTrack"), ResourceType::Media);
+        assert_eq!(
+            ResourceType::from_cdp("CSPViolationReport"),
+            ResourceType::Ping
+        );
+        assert_eq!(ResourceType::from_cdp("Manifest"), ResourceType::Other);
+        assert_eq!(ResourceType::from_cdp(""), ResourceType::Other);
+        assert_eq!("fetch".parse::<ResourceType>(), Ok(ResourceType::Fetch));
+    }
+
+    #[test]
+    fn roundtrips_through_cdp_spelling() {
+        use ResourceType::*;
+        for t in [
+            Document,
+            SubDocument,
+            Script,
+            Stylesheet,
+            Image,
+            Font,
+            Media,
+            Xhr,
+            Fetch,
+            WebSocket,
+            EventSource,
+            Ping,
+            Other,
+        ] {
+            assert_eq!(ResourceType::from_cdp(t.as_str()), t);
+            assert_eq!(t.to_string(), t.as_str());
+        }
+    }
+
+    #[test]
+    fn adblock_spelling() {
+        assert_eq!(ResourceType::Fetch.as_adblock_str(), "xmlhttprequest");
+        assert_eq!(ResourceType::SubDocument.as_adblock_str(), "subdocument");
+        assert!(ResourceType::Fetch.is_xhr());
+        assert!(!ResourceType::Script.is_xhr());
+        assert!(ResourceType::Font.is_visual());
+        assert!(!ResourceType::Script.is_visual());
+    }
+}
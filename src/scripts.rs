@@ -1,606 +1,15 @@
-use crate::trie::Trie;
+use crate::substring::SubstringMatcher;
 use std::sync::LazyLock;
 
-/// Ignore list of scripts.
-pub static URL_IGNORE_TRIE: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        "https://www.googletagservices.com/tag/",
-        "https://js.hs-analytics.net/analytics/",
-        "https://js.usemessages.com/conversations-embed.js",
-        "https://www.googletagmanager.com/",
-        "https://googletagmanager.com/",
-        "https://cm.g.doubleclick.net/",
-        "https://www.googleoptimize.com/optimize.js?id=GTM",
-        "https://ads.pubmatic.com/AdServer/",
-        "https://js.hsadspixel.net",
-        "https://www.google.com/adsense/",
-        "https://www.googleadservices.com/",
-        "https://static.cloudflareinsights.com/",
-        "https://cdn.curalate.com/sites/",
-        "https://adservice.google.com",
-        "https://www.gstatic.com/cv/js/sender/",
-        "https://googleads.g.doubleclick.net",
-        "https://www.google-analytics.com",
-        "https://www.googleanalytics.com",
-        "https://cdn-cookieyes.com/client_data/",
-        "https://iabusprivacy.pmc.com/geo-info.js",
-        "https://support.webtasy.com/scripts/track_visit.php",
-        "https://cookie-cdn.cookiepro.com/consent",
-        "https://a.omappapi.com/app/js/api.min.js",
-        "https://static.hotjar.com/",
-        "https://cdn.eu.amplitude.com/script/",
-        "https://js.hscta.net/cta/current.js",
-        "https://cdn.refersion.com/refersion.js",
-        "https://web.cmp.usercentrics.eu/ui/loader.js",
-        "https://pixeltrack.clientgear.com",
-        "https://events.framer.com/script?v=2",
-        "https://load.sumome.com/",
-        "https://websdk.appsflyer.com/",
-        "https://cdn.mediago.io/js/pixel.js",
-        "https://s.pinimg.com/ct/core.js",
-        "https://www.mongoosemetrics.com/",
-        "https://geolocation-recommendations.shopifyapps.com/",
-        "https://consent.cookiebot.com/uc.js",
-        "https://w.usabilla.com/",
-        "https://consentcdn.cookiebot.com/",
-        "https://plausible.io/api/event",
-        "https://sentry.io/api/",
-        "https://cdn.onesignal.com/",
-        "https://cdn.cookielaw.org/",
-        "https://static.doubleclick.net",
-        "https://tools.luckyorange.com/",
-        "https://cdn.piano.io",
-        "https://px.ads.linkedin.com",
-        "https://px4.ads.linkedin.com",
-        "https://cdn.sift.com/s.js",
-        "https://connect.facebook.net",
-        "https://tags.tiqcdn.com",
-        "https://tr.snapchat.com",
-        "https://ads.twitter.com",
-        "https://cdn.segment.com",
-        "https://stats.wp.com",
-        "https://analytics.",
-        "http://analytics.",
-        "https://cdn.cxense.com",
-        "https://cdn.tinypass.com",
-        "https://cd.connatix.com",
-        "https://js.chargebee.com/v2/chargebee.js",
-        "https://consent.cookiebot.com/",
-        "https://platform-api.sharethis.com/js/sharethis.js",
-        "https://js.hsforms.net/forms/embed/v2.js",
-        "https://static.parastorage.com/services/wix-thunderbolt/dist/",
-        "https://static.parastorage.com/services/tag-manager-client/",
-        "https://cdn.consentmanager.net/",
-        "https://static.parastorage.com/services/form-app/",
-        "https://www.datadoghq-browser-agent.com/",
-        "https://b.delivery.consentmanager.net/delivery/",
-        "https://tvem.cdn.turner.com/v2/",
-        "https://image6.pubmatic.com/AdServer/",
-        "https://static.criteo.net/js/ld/ld.js",
-        "https://www.digistore24.com/track/AFFILIATE/",
-        "https://i.cdn.turner.com/ads/adfuel/",
-        "https://featureassets.org",
-        "https://cdn.rudderlabs.com",
-        "https://script.hotjar.com/",
-        "https://cdn.branch.io/branch-latest.min.js",
-        "https://cdn.brcdn.com/v1/",
-        "https://cdn.scarabresearch.com/js/",
-        "https://cdn.insurads.com/",
-        "https://cdn.segment.com/",
-        "https://analytics.tiktok.com/",
-        "https://cdn-ukwest.onetrust.com",
-        "https://cdn.onetrust.com",
-        "https://services.insurads.com/",
-        "https://platform.iteratehq.com/loader.js",
-        "https://cdn.complyauto.com/cookiebanner/banner.js",
-        "https://sdtagging.azureedge.net/scripts/sd.js?containerId=GMUS",
-        "https://ref.dealerinspire.com/",
-        "https://privacy.condenastdigital.com/",
-        "https://cas-assets.edmunds.com/partner-analytics/",
-        "https://s.amazon-adsystem.com/",
-        "https://ts.amazon-adsystem.com/",
-        "https://tracking.g2crowd.com/attribution_tracking/conversions/",
-        "https://wsmcdn.audioeye.com/aem.js",
-        "https://static.criteo.net/",
-        "https://dynamic.criteo.com/",
-        "https://tags.creativecdn.com/",
-        "https://static.ads-twitter.com/uwt.js",
-        "https://amplify.outbrain.com/cp/obtp.js",
-        "https://js.hsadspixel.net/fb.js",
-        "https://js.hs-banner.com/v2/",
-        "https://js.hsadspixel.net/pixels.js",
-        "https://www.dwin1.com/2134.js",
-        "https://chronicle.blueconic.net/DG/DEFAULT/cs?&",
-        "https://munchkin.marketo.net/munchkin.js",
-        "https://servedby.flaviar.com/app.js",
-        "https://browser.sentry-cdn.com/",
-        "https://www.dianomi.com/js/contextfeed.js",
-        "https://a.pub.network/",
-        "https://secure.quantserve.com/quant.js",
-        "https://launchpad-wrapper.privacymanager.io/",
-        "https://cdn.quantummetric.com/qscripts/",
-        "https://get.s-onetag.com",
-        "https://wix-engage-visitors-prod-13.firebaseio.com/",
-        "https://c.amazon-adsystem.com/aax2/apstag.js",
-        "https://servedbyadbutler.com/",
-        "https://www.clarity.ms/tag/",
-        "https://tru.am/scripts/custom/",
-        "https://js.zi-scripts.com/zi-tag.js",
-        "https://tag.getamigo.io/js/",
-        "https://s.go-mpulse.net/boomerang/",
-        "https://tag.aticdn.net/piano-analytics.js",
-        "https://cdn.trustcommander.net/privacy/",
-        "https://img1.wsimg.com/traffic-assets/js/tccl-tti.min.js",
-        "https://img1.wsimg.com/signals/js/clients/scc-c2/scc-c2.min.js",
-        "https://beacon.riskified.com/",
-        "https://connect.facebook.net/en_US/fbevents.js",
-        "https://cdn.shopify.com/shopifycloud/boomerang/shopify-boomerang-1.0.0.min.js",
-        "https://cdn-ukwest.onetrust.com/scripttemplates/otSDKStub.js",
-        "https://tag.marinsm.com/serve/",
-        "https://acdn.adnxs.com/ast/ast.js",
-        "https://log.medietall.no/analytics.v2.js",
-        "https://adsdk.microsoft.com/ast/ast.js",
-        "https://schibsted-cdn.relevant-digital.com/static/tags/",
-        "https://sdk.privacy-center.org/",
-        "https://bat.bing.net",
-        "https://tpc.googlesyndication.com/",
-        "https://cdn.petametrics.com/",
-        "https://cdn.doubleverify.com/",
-        "https://assets.poool.fr/engage.min.js",
-        "https://www.facebook.com/v17.0/plugins/like.php?",
-        "https://gum.criteo.com",
-        "https://js-sec.indexww.com",
-        "https://eus.rubiconproject.com/",
-        "https://eb2.3lift.com/",
-        "https://acdn.adnxs.com/",
-        "https://ssc-cms.33across.com/",
-        "https://static.addtoany.com/menu/",
-        "https://www.gstatic.com/cast/sdk/libs/sender/1.0/cast_framework.js",
-        "https://www.gstatic.com/eureka/clank/131/cast_sender.js",
-        "https://static.adsafeprotected.com/",
-        "https://ssum-sec.casalemedia.com/usermatch",
-        "https://cdn.brandmetrics.com/scripts/",
-        "https://cdn.confiant-integrations.net/",
-        "https://www.b2i.us/b2i/",
-        "https://acsbapp.com/apps/app/dist/js/app.js",
-        "https://cdn.doofinder.com/livelayer/",
-        "https://load.sumo.com/",
-        "https://cdn11.bigcommerce.com/",
-        "https://na.shgcdn3.com/collector.js",
-        "https://microapps.bigcommerce.com/bodl-events/index.js",
-        "https://checkout-sdk.bigcommerce.com/v1/loader.js",
-        "https://cdn.callrail.com/companies/",
-        "https://www.webtraxs.com/trxscript.php",
-        "https://diffuser-cdn.app-us1.com/diffuser/diffuser.js",
-        "https://imasdk.googleapis.com/js/sdkloader/ima3.js",
-        "https://cdn.registerdisney.go.com/v4/responder.js",
-        "https://cdn.registerdisney.go.com/v4/OneID.js",
-        "https://u5.investingchannel.com/static/uat.js",
-        "https://js-agent.newrelic.com/",
-        "https://bat.bing.com/",
-        "https://scripts.webcontentassessor.com/",
-        "https://container.pepperjam.com/",
-        "https://p.cquotient.com/pebble?",
-        "https://p.yotpo.com/i?",
-        "https://cdn-gl.imrworldwide.com/",
-        "https://usage.trackjs.com/",
-        "https://shopify-init.blackcrow.ai/",
-        "https://js.adsrvr.org/up_loader.1.1.0.js",
-        "https://t.cfjump.com/tag/",
-        "https://tag.wknd.ai/",
-        "https://bpi.zip.co/trx/3.3.0.js",
-        "https://s1.hdslb.com/bfs/cm/cm-sdk/static/js/track-collect.js",
-        "https://consent.trustarc.com/",
-        "https://cdn-sitegainer.com/",
-        "https://yob9p0yb4y.kameleoon.eu/",
-        "https://j.6sc.co/",
-        "https://b.6sc.co/",
-        "https://c.6sc.co/",
-        "https://ipv6.6sc.co/",
-        "https://epsilon.6sense.com/",
-        "https://x.clearbitjs.com/v2/",
-        "https://api.clerk.io/v2/log/",
-        "https://cdn.noibu.com/",
-        "https://us1.discourse-cdn.com/cursor1/assets/plugins/discourse-adplugin",
-        "https://static.cloudflareinsights.com/beacon.min.js/",
-        "https://hm.baidu.com/",
-        "https://cdn.wootric.com/",
-        "https://eligibility.wootric.com/",
-        // LogRocket first-party analytics infra — blocking these breaks
-        // logrocket.com's own pages (client-side exception on hydration).
-        // "https://storage.googleapis.com/lr-marketing-js/lr-web-analytics/",
-        // "https://relay.lr-wa.com/",
-        "https://unpkg.zhimg.com/@efe/zhad-tracker",
-        "https://tracking.g2crowd.com/attribution_tracking/",
-        "https://snap.licdn.com/",
-        "https://m.servedby-buysellads.com/monetization.it.js",
-        "https://cdn.metadata.io/site-insights.js",
-        "https://www.ist-track.com/",
-        "https://www.redditstatic.com/ads/",
-        "https://verifi.podscribe.com/",
-        "https://script.crazyegg.com/",
-        "https://cdn.iubenda.com/",
-        "https://bundle.5gtb.com/",
-        "https://platform.linkedin.com/in.js",
-        "https://app.qualified.com/w/",
-        "https://d34r8q7sht0t9k.cloudfront.net/tag.js",
-        "https://pagead2.googlesyndication.com/",
-        "https://a.klaviyo.com/onsite/track-analytics",
-        "https://apps.bazaarvoice.com/analytics/bv-analytics.js",
-        "https://mab.chartbeat.com/mab_strategy/",
-        "https://c.amazon-adsystem.com/",
-        "https://rumcdn.geoedge.be/",
-        "https://assets.adobedtm.com/",
-        "https://macro.adnami.io/macro/spec/adsm.macro.",
-        "https://log.medietall.no/analytics.js",
-        "https://cl-eu2.k5a.io/",
-        "https://cdn.siftscience.com/s.js",
-        "https://cdn.segment.com/analytics.js",
-        "https://lwadm.com/lw/pbjs?",
-        "https://cl.k5a.io/",
-        "https://cdn-cookieyes.com/",
-        "https://s.kk-resources.com/leadtag.js",
-        "https://app.optibase.io/script.js",
-        "https://nexus.ensighten.com/",
-        "https://c.oracleinfinity.io/acs/account/fp3kyrmvtg/js/prod/odc.js",
-        "https://static-tracking.klaviyo.com/",
-        "https://cdn-widgetsrepository.yotpo.com/",
-        "https://embed.sendtonews.com/player3/embedcode.js?",
-        "https://a.klaviyo.com/onsite/track-analytics?",
-        "https://vi.ml314.com/get?",
-        "https://ads.empowerlocal.co/adserve/",
-        "https://pub.doubleverify.com/",
-        "https://klaviyo.com/onsite/track-analytics?",
-        "https://s2.go-mpulse.net/",
-        "https://pbs.yahoo.com/",
-        "https://cdn.intergient.com/",
-        "https://img1.wsimg.com/",
-        "https://invitejs.trustpilot.com/tp.min.js",
-        "https://ads.pubmatic.com/AdServer/js/",
-        "https://dev.visualwebsiteoptimizer.com/",
-        "https://cdn.cxense.com/cx.cce.js",
-        "https://widgets.outbrain.com/nanoWidget/externals/obPixelFrame/obPixelFrame.js",
-        "https://widgets.outbrain.com/external/externals/intentiq.js",
-        "https://applets.ebxcdn.com/ebx.js",
-        "https://cdn.yektanet.com/superscript/",
-        "https://cdn.fuseplatform.net/publift/tags/",
-        "https://tag.rmp.rakuten.com/",
-        "https://analytics-api.",
-        "https://cdn.corvidae.ai/pixel.min.js",
-        "https://app.popt.in/pixel.js",
-        "https://js-agent.newrelic.com",
-        "https://js-eu1.hs-analytics.net/analytics/",
-        "https://js-eu1.hs-banner.com/v2/",
-        "https://static.doubleclick.net/instream/ad_status.js",
-        "ssl.cf1.rackcdn.com/stf.js",
-        "https://code.etracker.com/code/e.js",
-        "https://geo.privacymanager.io/",
-        "https://script.dotmetrics.net/",
-        "https://delivery.consentmanager.net/delivery/",
-        "https://mc.yandex.ru/metrika/tag.js",
-        "https://cdn.debugbear.com/",
-        "https://stats.wp.com/e-",
-        "https://public.servenobid.com/partner/",
-        "https://sb.scorecardresearch.com/",
-        "https://cdn.pdst.fm/ping.min.js",
-        "https://www.ezojs.com/ezoic/sa.min.js",
-        "https://resources.infolinks.com/js/infolinks_main.js",
-        "https://www.clarity.ms/tag/shopify/",
-        "https://btloader.com/tag?",
-        "https://api.factors.ai",
-        "https://app.factors.ai",
-        "https://static.klaviyo.com/onsite/js/",
-        "https://a.klaviyo.com/media/js/",
-        "https://cdn.intelligems.io/esm/",
-        "https://cdn.privacy-mgmt.com/unified/wrapperMessagingWithoutDetection.js",
-        "https://client.aps.amazon-adsystem.com/publisher.js",
-        "https://s.go-mpulse.net/",
-        "https://static.chartbeat.com/js/chartbeat_video.js",
-        "https://static.chartbeat.com/js/chartbeat_mab.js",
-        "https://assets.trendii.com/pixel/main.js",
-        "https://micro.rubiconproject.com/prebid/",
-        "https://player.anyclip.com/anyclip-widget/",
-        "https://try.abtasty.com/",
-        "https://tags.tiqcdn.com/utag/",
-        "https://assets.squarespace.com/universal/scripts-compressed/performance-",
-        "https://assets.squarespace.com/universal/scripts-compressed/async-gdpr-cookie-banner-",
-        "https://cdnjs.cloudflare.com/ajax/libs/cookieconsent2/3.0.3/cookieconsent.min.js",
-        "https://libs.coremetrics.com/eluminate.js",
-        "https://c.webtrends-optimize.com/acs/accounts/",
-        "https://cmp.inmobi.com/choice/",
-        "https://p11.techlab-cdn.com/",
-        "https://us-assets.i.posthog.com/",
-        "https://cdn.mxpnl.com/",
-        "https://fs.fullstory.com/",
-        "https://edge.fullstory.com/",
-        "https://cdn.pendo.io/",
-        "https://cdn.logrocket.io/",
-        "https://cdn.heapanalytics.com/",
-        "https://cdn.mouseflow.com/",
-        "https://web-sdk.smartlook.com/",
-        "https://cdn.inspectlet.com/",
-        "https://s.adroll.com/",
-        "https://jssdkcdn.mparticle.com/",
-        "https://cdn.adjust.com/",
-        "https://www.statcounter.com/counter/",
-        "/scripts/matomo/",
-        "/js/exponea.min.js",
-        "/eBootsStorefrontAssetStore/javascript/Analytics.js",
-        "/eu_cookie_compliance/js/eu_cookie_compliance.min.js",
-        "/privacy-banner/storefront-banner.js",
-        "/consent-tracking-api/v0.1/consent-tracking-api.js",
-        "/simpleads/impression",
-        "/setup-sentry.js",
-        "/setup-intercom-monitoring.js",
-        "go-mpulse.net/boomerang",
-        ".siteintercept.qualtrics.com",
-        ".sharethis.com",
-        ".cdn4.forter.com",
-        ".newrelic.com",
-        ".googlesyndication.com",
-        ".amazon-adsystem.com",
-        ".onetrust.com",
-        "sc.omtrdc.net",
-        "doubleclick.net",
-        "hotjar.com",
-        // "datadome.com",
-        "datadog-logs-us.js",
-        "tinypass.min.js",
-        "tracker.js",
-        "tracker.min.js",
-        "matomo.js",
-        ".airship.com",
-        ".adlightning.com",
-        ".lab.amplitude.",
-        ".posthog.com",
-        ".piwik.pro",
-        ".contentsquare.net",
-        // explicit ignore tracking.js and ad files
-        "privacy-notice.js",
-        "cookie_consent.js",
-        "tracking.js",
-        "accept-cookie.js",
-        "privacy_cookie.js",
-        // google cast not required
-        "cast_sender.js",
-        "eu_cookie_compliance.js",
-        "eu_cookie_compliance.min.js",
-        "plugins/cookie-law-info/legacy/",
-        "ads.js",
-        "ads.min.js",
-        "insight.min.js",
-        "assets/TrackingPixel",
-        "cookieconsent.min.js",
-        "cookieconsent.js",
-        "cookie-consent.",
-        "adsbygoogle.js",
-        "otBannerSdk.js",
-        "otBannerSdk.min.js",
-        "tracking.min.js",
-        "trackings-min.",
-        "analytics.min.js",
-        "analytics.js", // we may want this optional as some views may be called analytics.js
-        ".pubmatic.com/AdServer/",
-        "/track",
-        "https://ads.",
-        "http://ads.",
-        "https://ads-static.",
-        "https://tracking.",
-        "http://tracking.",
-        "https://static-tracking.",
-        // ChiliPiper meeting widget embedded as CNAMEd subdomain (observed
-        // at logrocket.chilipiper.com — not first-party content).
-        "https://logrocket.chilipiper.com",
-        // Twitter/X conversion pixel. Narrow to `/adsct` + `/1/i/adsct` so
-        // legitimate `t.co/...` link-shortener redirects still resolve.
-        "https://t.co/i/adsct",
-        "https://t.co/1/i/adsct",
-        // Google Ads 1p-conversion / 1p-user-list pixel paths. Scoped to the
-        // `/pagead/` path so regular google.com search/service URLs pass.
-        "https://www.google.com/pagead/",
-        // exp testin
-        // used for possible location outside
-        // "https://www.recaptcha.net/recaptcha/",
-        // "https://www.google.com/recaptcha/",
-        // "https://www.gstatic.com/recaptcha/",
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
+include!(concat!(env!("OUT_DIR"), "/scripts_tries.rs"));
 
-/// Ignore list of scripts embedded or font extra.
-pub static URL_IGNORE_EMBEDED_TRIE: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        "https://www.youtube.com/embed/",         // YouTube video embeds
-        "https://www.google.com/maps/embed?",     // Google Maps embeds
-        "https://maps.google.com",                // Google maps iframe.
-        "https://player.vimeo.com/video/",        // Vimeo video embeds
-        "https://player.vimeo.com/api/player.js", // Vimeo video embeds
-        "https://open.spotify.com/embed/",        // Spotify music embeds
-        "https://w.soundcloud.com/player/",       // SoundCloud embeds
-        "https://platform.twitter.com/embed/",    // Twitter embedded tweets
-        "https://www.instagram.com/embed.js",     // Instagram embeds
-        "https://www.facebook.com/plugins/",      // Facebook embeds (like posts and videos)
-        "https://cdn.embedly.com/widgets/",       // Embedly embeds
-        "https://player.twitch.tv/",              // Twitch video player embeds
-        "https://maps.googleapis.com/maps/",      // Google map embeds
-        "https://www.youtube.com/player_api",     // Youtube player.
-        "https://consentcdn.cookiebot.com",       // Cookie bot
-        "https://www.youtube.com/iframe_api",     // Youtube iframes.
-        "https://f.vimeocdn.com",                 // Vimeo EMBEDDINGS
-        "https://i.vimeocdn.com/",
-        "https://image2.pubmatic.com/AdServer/",
-        "https://ads.pubmatic.com/AdServer/js/",
-        "https://cdn.taboola.com/libtrc/static/topics/",
-        "https://pm-widget.taboola.com/",
-        "https://p.cquotient.com/pebble",
-        "https://globale-analytics-sdk.global-e.com/PROD/bundle.js",
-        "https://gum.criteo.com/syncframe",
-        "https://cdn.heapanalytics.com/js/",
-        "https://cdn.intellimize.co/",
-        "https://td.doubleclick.net/td/",
-        "https://gum.criteo.com/syncframe?",
-        // "https://js.datadome.co/",
-        "https://www.googletagmanager.com/",
-        "https://secure.livechatinc.com/customer/action/open_chat", // chat
-        "setup-intercom-monitoring.js",
-        "setup-sentry.js",
-        "cast_sender.js",
-        // "https://www.youtube.com/s/player/", // Youtube player not needed usually since iframe_api is used mainly
-        // vercel live
-        "https://vercel.live/api/",
-        // Shop app pay analytics iframe.
-        "https://shop.app/pay/hop?analytics_trace_id",
-        // extra CDN scripts
-        "https://cdn.readme.io/public/",
-        // font awesome
-        "https://use.fontawesome.com/",
-        // insight tracker
-        "https://insight.adsrvr.org/track/",
-        "http://www.google-analytics.com/ga.js",
-        ".optimizely.com/client_storage/",
-        "cxense.com/",
-        // snapchat tracker
-        "https://tr.snapchat.com/",
-        "https://buy.tinypass.com",
-        "https://nimbleplot.com/",
-        "https://my.actiondata.co/js/tracker.php",
-        "https://ajax.googleapis.com/ajax/libs/webfont/",
-        "http://cdn2.editmysite.com/",
-        "https://cmp.osano.com/",
-        "https://api.exponea.com/js/service-worker.min.js",
-        // ignore font extras
-        "https://kit.fontawesome.com/",
-        "https://perf-na1.hsforms.com/embed/",
-        "https://use.typekit.net",
-        ".amplitude.com",
-        ".rudderstack.com",
-        // ignore tailwind cdn
-        "https://cdn.tailwindcss.com",
-        // ignore extra ads
-        ".sharethis.com",
-        "amazon-adsystem.com",
-        ".vimeocdn.com",
-        "g.doubleclick.net",
-        "https://securepubads.g.doubleclick.net",
-        "googlesyndication.com",
-        "adsafeprotected.com",
-        "analytics.min.js",
-        // more google tracking
-        ".googlesyndication.com/safeframe/",
-        // repeat consent js
-        "/ccpa/user-consent.min.js",
-        "consent-manager",
-        "/cookiebanner/js/",
-        "cookielaw.org",
-        "bugsnag.min.js",
-        // privacy
-        "otBannerSdk.js",
-        "privacy-notice.js",
-        ".ingest.sentry.io/api",
-        // ignore amazon scripts for media
-        ".ssl-images-amazon.com/images/",
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
+/// [`URL_IGNORE_SCRIPT_BASE_PATHS`] as a substring matcher for full URLs.
+pub static URL_IGNORE_SCRIPT_BASE_PATHS_MATCHER: LazyLock<SubstringMatcher> =
+    LazyLock::new(|| SubstringMatcher::from_static_trie(&URL_IGNORE_SCRIPT_BASE_PATHS));
 
-/// Ignore list of path scripts to ignore for tracking and analytics.
-pub static URL_IGNORE_SCRIPT_BASE_PATHS: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        "wp-content/plugins/cookie-law-info",
-        "wp-content/plugins/borlabs-cookie/",
-        "wp-content/js/rlt-proxy.js",
-        "wp-admin/rest-proxy/",
-        "wp-content/mu-plugins/a8c-analytics/",
-        "analytics/",
-        "cookie-tracking",
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
-
-/// Ignore list of path scripts to ignore for themes.
-pub static URL_IGNORE_SCRIPT_STYLES_PATHS: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        "wp-content/themes/",
-        "wp-content/plugins/dizo-image-hover/",
-        "wp-content/plugins/supreme-modules-pro-for-divi/",
-        "wp-content/plugins/page-builder-pmc/",
-        "wp-content/plugins/contact-form-7/",
-        "wp-content/plugins/responsive-lightbox/",
-        "wp-content/cache/breeze-minification/",
-        "wp-includes/js/mediaelement",
-        "wp-content/plugins/gravityforms/",
-        "wp-content/plugins/wp-rocket/assets/js/lazyload/",
-        "wp-content/plugins/w3-total-cache/",
-        "wp-content/js/bilmur.min.js",
-        "npm/bootstrap@",
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
-
-/// Ignore list of CSS resources.
-pub static URL_IGNORE_CSS: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        "https://www.youtube.com/generate_",
-        "https://www.google.com/generate_",
-        "https://fonts.googleapis.com/icon",
-        "https://www.youtube.com/sw.js_data",
-        "https://play.google.com/log",
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
-
-/// Ignore list of scripts paths.
-pub static URL_IGNORE_TRIE_PATHS: LazyLock<Trie> = LazyLock::new(|| {
-    let mut trie = Trie::new();
-    let patterns = [
-        // explicit ignore tracking.js and ad files
-        "privacy-notice.js",
-        "tracking.js",
-        "track.js",
-        "ads.js",
-        "analytics.js",
-        // minified variants (min.js handling)
-        "privacy-notice.min.js",
-        "tracking.min.js",
-        "track.min.js",
-        "ads.min.js",
-        "analytics.min.js",
-        "otSDKStub.js",
-        "otBannerSdk.js",
-        "_vercel/insights/script.js",
-        "analytics.", // keep this if you rely on prefix-y analytics.* patterns
-    ];
-    for pattern in &patterns {
-        trie.insert(pattern);
-    }
-    trie.shrink_to_fit();
-    trie
-});
+/// [`URL_IGNORE_TRIE_PATHS`] as a substring matcher for full URLs.
+pub static URL_IGNORE_TRIE_PATHS_MATCHER: LazyLock<SubstringMatcher> =
+    LazyLock::new(|| SubstringMatcher::from_static_trie(&URL_IGNORE_TRIE_PATHS));
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,256 @@
+//! Multi-pattern substring matcher (Aho–Corasick).
+//!
+//! Reuses the packed [`Trie`] arena as the goto function and adds two side
+//! arrays per node: a failure link and an output link (nearest node on the
+//! failure chain where a pattern ends). A lookup is a single left-to-right
+//! pass over the input, so fragments like `analytics.js` or
+//! `wp-content/plugins/cookie-law-info` match anywhere in a full URL without
+//! the caller slicing it first.
+//!
+//! Patterns built from a [`StaticTrie`] keep its expiry cutoffs; an expired
+//! pattern is passed over as if it were not stored.
+//!
+//! Immutable after construction; all reads go through `&self`.
+
+use crate::trie::{unix_now, StaticTrie, Trie, ROOT};
+
+/// Output link marking "no pattern ends on this failure chain".
+const NO_OUTPUT: u32 = u32::MAX;
+
+/// Expiry cutoff of a pattern that never expires.
+const NEVER: u64 = u64::MAX;
+
+/// A stored pattern found inside a lookup input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubstringMatch<'a> {
+    /// The matched pattern, borrowed from the lookup input.
+    pub pattern: &'a str,
+    /// Byte offset of the match start in the input.
+    pub start: usize,
+    /// Byte offset one past the match end in the input.
+    pub end: usize,
+}
+
+/// Aho–Corasick automaton over a frozen [`Trie`].
+#[derive(Debug)]
+pub struct SubstringMatcher {
+    /// Goto function, with each pattern's expiry cutoff as its payload.
+    /// Always frozen.
+    trie: Trie<u64>,
+    /// Failure link per node.
+    fail: Box<[u32]>,
+    /// Nearest node (self included) on the failure chain where a pattern
+    /// ends, or `NO_OUTPUT`.
+    output: Box<[u32]>,
+    /// Depth of each node, i.e. the length of the pattern ending there.
+    depth: Box<[u32]>,
+}
+
+impl Default for SubstringMatcher {
+    fn default() -> Self {
+        Self::new(std::iter::empty())
+    }
+}
+
+impl SubstringMatcher {
+    /// Build a matcher from `patterns`. Empty patterns are ignored.
+    pub fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut trie = Trie::default();
+        for p in patterns {
+            if !p.is_empty() {
+                trie.insert_value(p, NEVER);
+            }
+        }
+        Self::build(trie)
+    }
+
+    /// Build a matcher from every pattern stored in an existing prefix trie.
+    pub fn from_trie<V>(source: &Trie<V>) -> Self {
+        Self::from_entries(|f| source.for_each_word(|word| f(word, None)))
+    }
+
+    /// Build a matcher from every pattern stored in a [`StaticTrie`], e.g.
+    /// `URL_IGNORE_TRIE_PATHS`, keeping their expiry cutoffs.
+    pub fn from_static_trie(source: &StaticTrie<'_>) -> Self {
+        Self::from_entries(|f| source.for_each_entry(f))
+    }
+
+    fn from_entries(for_each_entry: impl FnOnce(&mut dyn FnMut(&[u8], Option<u64>))) -> Self {
+        let mut trie = Trie::default();
+        for_each_entry(&mut |bytes, expires| {
+            if let Ok(word) = std::str::from_utf8(bytes) {
+                if !word.is_empty() {
+                    trie.insert_value(word, expires.unwrap_or(NEVER));
+                }
+            }
+        });
+        Self::build(trie)
+    }
+
+    fn build(mut trie: Trie<u64>) -> Self {
+        trie.shrink_to_fit();
+        let n = trie.node_count();
+        let mut fail = vec![ROOT; n];
+        let mut output = vec![NO_OUTPUT; n];
+        let mut depth = vec![0u32; n];
+
+        // Breadth-first so every failure target is finalized before the
+        // nodes that point at it.
+        let mut queue = std::collections::VecDeque::with_capacity(n);
+        queue.push_back(ROOT);
+        while let Some(u) = queue.pop_front() {
+            for (byte, v) in trie.edges(u) {
+                depth[v as usize] = depth[u as usize] + 1;
+                if u != ROOT {
+                    let mut f = fail[u as usize];
+                    fail[v as usize] = loop {
+                        if let Some(next) = trie.child(f, byte) {
+                            break next;
+                        }
+                        if f == ROOT {
+                            break ROOT;
+                        }
+                        f = fail[f as usize];
+                    };
+                }
+                output[v as usize] = if trie.is_terminal(v) {
+                    v
+                } else {
+                    output[fail[v as usize] as usize]
+                };
+                queue.push_back(v);
+            }
+        }
+
+        Self {
+            trie,
+            fail: fail.into_boxed_slice(),
+            output: output.into_boxed_slice(),
+            depth: depth.into_boxed_slice(),
+        }
+    }
+
+    /// Check if any stored pattern occurs anywhere in `text`.
+    #[inline]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Find the first (earliest-ending) occurrence of a live stored pattern
+    /// in `text`. When several patterns end at the same byte, the longest
+    /// wins.
+    pub fn find<'a>(&self, text: &'a str) -> Option<SubstringMatch<'a>> {
+        let mut state = ROOT;
+        for (i, &b) in text.as_bytes().iter().enumerate() {
+            state = loop {
+                if let Some(next) = self.trie.child(state, b) {
+                    break next;
+                }
+                if state == ROOT {
+                    break ROOT;
+                }
+                state = self.fail[state as usize];
+            };
+            let mut out = self.output[state as usize];
+            while out != NO_OUTPUT && !self.is_live(out) {
+                out = self.output[self.fail[out as usize] as usize];
+            }
+            if out != NO_OUTPUT {
+                let end = i + 1;
+                let start = end - self.depth[out as usize] as usize;
+                // Patterns are `&str`s whose bytes equal `text[start..end]`,
+                // so both ends fall on char boundaries.
+                return Some(SubstringMatch {
+                    pattern: &text[start..end],
+                    start,
+                    end,
+                });
+            }
+        }
+        None
+    }
+
+    /// `false` if the pattern ending at node `idx` has expired.
+    #[inline]
+    fn is_live(&self, idx: u32) -> bool {
+        let at = *self.trie.value(idx);
+        at == NEVER || unix_now() < at
+    }
+
+    /// Number of stored patterns.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// `true` if no pattern is stored.
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_anywhere_in_input() {
+        let m = SubstringMatcher::new(["analytics.js", "-advertisement.", "cookie-law-info"]);
+        assert!(m.is_match("https://example.com/static/analytics.js?v=1"));
+        assert!(m.is_match("https://cdn.example.com/img/top-advertisement.png"));
+        assert!(m.is_match("https://example.com/wp-content/plugins/cookie-law-info/a.js"));
+        assert!(!m.is_match("https://example.com/app.js"));
+        assert!(!m.is_match(""));
+    }
+
+    #[test]
+    fn find_reports_position() {
+        let m = SubstringMatcher::new(["track.js", "ads.js"]);
+        let hit = m.find("https://x.com/js/ads.js").unwrap();
+        assert_eq!(hit.pattern, "ads.js");
+        assert_eq!(hit.start, "https://x.com/js/".len());
+        assert_eq!(hit.end, "https://x.com/js/ads.js".len());
+    }
+
+    #[test]
+    fn failure_links_recover_overlaps() {
+        // "abcd" fails at 'x'; the suffix "bc" must still be found via the
+        // failure link rather than restarting from the root.
+        let m = SubstringMatcher::new(["abcd", "bcx", "c"]);
+        assert_eq!(m.find("zabcx").unwrap().pattern, "c");
+        let m = SubstringMatcher::new(["abcd", "bcx"]);
+        assert_eq!(m.find("zabcx").unwrap().pattern, "bcx");
+        // Output links surface a shorter pattern ending inside a longer one.
+        let m = SubstringMatcher::new(["she", "he"]);
+        assert_eq!(m.find("ushers").unwrap().pattern, "she");
+    }
+
+    #[test]
+    fn expired_patterns_are_passed_over() {
+        let entries = [("she", Some(0)), ("he", None), ("hers", Some(NEVER - 1))];
+        let m = SubstringMatcher::from_entries(|f| {
+            for (word, expires) in entries {
+                f(word.as_bytes(), expires);
+            }
+        });
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.find("ushers").unwrap().pattern, "he");
+        assert!(!m.is_match("ash"));
+    }
+
+    #[test]
+    fn from_trie_matches_full_urls() {
+        use crate::scripts::URL_IGNORE_TRIE_PATHS;
+
+        let m = SubstringMatcher::from_static_trie(&URL_IGNORE_TRIE_PATHS);
+        assert!(m.is_match("https://example.com/assets/tracking.min.js"));
+        assert!(m.is_match("https://example.com/_vercel/insights/script.js"));
+        assert!(!m.is_match("https://example.com/assets/main.js"));
+    }
+
+    #[test]
+    fn empty_matcher_matches_nothing() {
+        let m = SubstringMatcher::default();
+        assert!(m.is_empty());
+        assert!(!m.is_match("anything"));
+    }
+}
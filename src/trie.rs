@@ -6,17 +6,30 @@
 //! friendly for the typical 1–4 children per node, falling back to a binary
 //! search at higher fan-out.
 //!
+//! Patterns can carry a payload: `Trie<V>` keeps one `V` per stored pattern
+//! in a side arena indexed from the terminal node, so nodes stay the same
+//! size whatever `V` is. The plain `Trie` is `Trie<()>` — a pure set.
+//!
 //! The trie is append-only during construction and fully immutable after the
 //! last `insert`. All reads go through `&self`, no interior mutability, no
 //! locks — safe to share across threads via `Arc` or `&'static` without any
 //! risk of blocking or deadlocks.
+//!
+//! [`StaticTrie`] is the same packed layout flattened into borrowed slices.
+//! `build.rs` emits the built-in lists in that form as plain `static`s, so
+//! they cost nothing at startup and never touch the heap. Patterns from a
+//! file with an `expires:` header keep their cutoff in a side table and stop
+//! matching once it passes.
 
 /// Linear-scan cutoff. At or below this edge count we scan; above, we binary
 /// search. Tuned for L1 cache lines and branch prediction on tiny fan-outs.
 const LINEAR_SCAN_THRESHOLD: usize = 8;
 
 /// Root node always lives at index 0.
-const ROOT: u32 = 0;
+pub(crate) const ROOT: u32 = 0;
+
+/// Value slot marking a node where no stored pattern ends.
+const NO_VALUE: u32 = u32::MAX;
 
 /// Packed trie node. Edge keys and child indices are stored as parallel
 /// sorted arrays so the lookup inner loop only touches the `keys` slice.
@@ -26,18 +39,36 @@ struct Node {
     keys: Box<[u8]>,
     /// Child node indices (into `Trie::nodes`), parallel to `keys`.
     children: Box<[u32]>,
-    /// Some stored pattern ends at this node.
-    is_end_of_word: bool,
+    /// Index into `Trie::values` if a stored pattern ends at this node,
+    /// `NO_VALUE` otherwise.
+    value: u32,
+}
+
+impl Node {
+    #[inline(always)]
+    fn is_end_of_word(&self) -> bool {
+        self.value != NO_VALUE
+    }
 }
 
 /// Mutable scratch children used only during `insert`. Discarded/frozen into
 /// `Node` on demand. Keeping the build-time `Vec` form avoids repeated
 /// allocate/shrink cycles of `Box<[_]>` per insert.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct BuildNode {
     keys: Vec<u8>,
     children: Vec<u32>,
-    is_end_of_word: bool,
+    value: u32,
+}
+
+impl Default for BuildNode {
+    fn default() -> Self {
+        BuildNode {
+            keys: Vec::new(),
+            children: Vec::new(),
+            value: NO_VALUE,
+        }
+    }
 }
 
 impl BuildNode {
@@ -45,14 +76,81 @@ impl BuildNode {
         Node {
             keys: self.keys.into_boxed_slice(),
             children: self.children.into_boxed_slice(),
-            is_end_of_word: self.is_end_of_word,
+            value: self.value,
         }
     }
 }
 
+/// A stored pattern that matched the start of a lookup input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefixMatch<'a> {
+    /// The matched pattern, borrowed from the lookup input.
+    pub pattern: &'a str,
+    /// Byte length of `pattern`.
+    pub len: usize,
+}
+
+/// Anything that reports which stored pattern a lookup input starts with.
+///
+/// Implemented by [`Trie`] and by shared or lazily built handles to one, so
+/// a trie loaded at runtime can stand wherever a `&'static Trie` could.
+pub trait PrefixMatcher {
+    /// The stored pattern `text` starts with, if any.
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>>;
+
+    /// Check if any stored pattern is a prefix of `text`.
+    #[inline]
+    fn contains_prefix(&self, text: &str) -> bool {
+        self.find_prefix(text).is_some()
+    }
+}
+
+impl<V> PrefixMatcher for Trie<V> {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        Trie::find_prefix(self, text)
+    }
+
+    #[inline]
+    fn contains_prefix(&self, text: &str) -> bool {
+        Trie::contains_prefix(self, text)
+    }
+}
+
+impl<M: PrefixMatcher + ?Sized> PrefixMatcher for &M {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        (**self).find_prefix(text)
+    }
+}
+
+impl<M: PrefixMatcher + ?Sized> PrefixMatcher for Box<M> {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        (**self).find_prefix(text)
+    }
+}
+
+impl<M: PrefixMatcher + ?Sized> PrefixMatcher for std::sync::Arc<M> {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        (**self).find_prefix(text)
+    }
+}
+
+impl<M: PrefixMatcher, F: FnOnce() -> M> PrefixMatcher for std::sync::LazyLock<M, F> {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        (**self).find_prefix(text)
+    }
+}
+
 /// Packed prefix trie. Built incrementally via `insert`; read-only afterwards.
+///
+/// `V` is the per-pattern payload (a category, rule id, source tag, ...).
+/// It defaults to `()`, which makes the trie a plain pattern set.
 #[derive(Debug)]
-pub struct Trie {
+pub struct Trie<V = ()> {
     /// All nodes contiguously. Index 0 is the root.
     nodes: Vec<Node>,
     /// Live build state. `Some` while we're still inserting, `None` once
@@ -61,26 +159,38 @@ pub struct Trie {
     /// via `LazyLock` — which is why we freeze inside `insert` at its tail
     /// and re-thaw on further inserts. See `ensure_build` / `freeze`.
     build: Option<Vec<BuildNode>>,
+    /// Payloads, one per stored pattern. Shared by the build and packed
+    /// forms — only node storage is converted on freeze/thaw.
+    values: Vec<V>,
 }
 
-impl Default for Trie {
+impl<V> Default for Trie<V> {
     fn default() -> Self {
-        Self::new()
+        Trie {
+            nodes: Vec::new(),
+            build: Some(vec![BuildNode::default()]),
+            values: Vec::new(),
+        }
     }
 }
 
 impl Trie {
     /// Create an empty trie.
     pub fn new() -> Self {
-        Trie {
-            nodes: Vec::new(),
-            build: Some(vec![BuildNode::default()]),
-        }
+        Self::default()
     }
 
     /// Insert a pattern. Multiple inserts are supported; the trie re-freezes
     /// after the final insert the next time it's read.
     pub fn insert(&mut self, word: &str) {
+        self.insert_value(word, ());
+    }
+}
+
+impl<V> Trie<V> {
+    /// Insert a pattern with its payload. Re-inserting an existing pattern
+    /// replaces its payload.
+    pub fn insert_value(&mut self, word: &str, value: V) {
         self.ensure_build();
         let build = self
             .build
@@ -101,7 +211,13 @@ impl Trie {
                 }
             };
         }
-        build[idx as usize].is_end_of_word = true;
+        let node = &mut build[idx as usize];
+        if node.value == NO_VALUE {
+            node.value = self.values.len() as u32;
+            self.values.push(value);
+        } else {
+            self.values[node.value as usize] = value;
+        }
     }
 
     /// Freeze the mutable build state into the packed read-only arena.
@@ -112,6 +228,7 @@ impl Trie {
                 nodes.push(b.freeze());
             }
             self.nodes = nodes;
+            self.values.shrink_to_fit();
         }
     }
 
@@ -127,7 +244,7 @@ impl Trie {
             build.push(BuildNode {
                 keys: n.keys.into_vec(),
                 children: n.children.into_vec(),
-                is_end_of_word: n.is_end_of_word,
+                value: n.value,
             });
         }
         if build.is_empty() {
@@ -167,6 +284,50 @@ impl Trie {
     /// concurrent use from any number of threads.
     #[inline]
     pub fn contains_prefix(&self, text: &str) -> bool {
+        self.prefix_slot(text).is_some()
+    }
+
+    /// Like [`Trie::contains_prefix`], but reports which pattern matched.
+    ///
+    /// Returns the shortest stored pattern that is a prefix of `text` — the
+    /// same one `contains_prefix` stops at — or `None` if nothing matches.
+    #[inline]
+    pub fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        self.prefix_slot(text)
+            .map(|(len, _)| prefix_match(text, len))
+    }
+
+    /// Like [`Trie::find_prefix`], also returning the matched pattern's
+    /// payload.
+    #[inline]
+    pub fn find_prefix_value<'a>(&self, text: &'a str) -> Option<(PrefixMatch<'a>, &V)> {
+        self.prefix_slot(text)
+            .map(|(len, slot)| (prefix_match(text, len), &self.values[slot as usize]))
+    }
+
+    /// Payload of the pattern exactly equal to `word`, if stored.
+    pub fn get(&self, word: &str) -> Option<&V> {
+        let slot = match &self.build {
+            Some(build) => exact_slot_build(build, word),
+            None => self.exact_slot_packed(word),
+        }?;
+        Some(&self.values[slot as usize])
+    }
+
+    /// Number of stored patterns.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// `true` if no pattern has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Byte length and value slot of the shortest stored pattern that
+    /// prefixes `text`.
+    #[inline]
+    fn prefix_slot(&self, text: &str) -> Option<(usize, u32)> {
         // If the trie was built but never frozen (still in build form),
         // walk the build form. This keeps the reader-side API `&self` with
         // no interior mutability; a caller that wants the packed form
@@ -174,38 +335,85 @@ impl Trie {
         // standard usage pattern is to build, then share by reference,
         // which freezes automatically below.
         if let Some(build) = &self.build {
-            return contains_prefix_build(build, text);
+            return prefix_slot_build(build, text);
         }
         let nodes = &self.nodes;
         if nodes.is_empty() {
-            return false;
+            return None;
         }
         let mut node = &nodes[ROOT as usize];
-        for &b in text.as_bytes() {
+        for (i, &b) in text.as_bytes().iter().enumerate() {
             match Self::find_child(node, b) {
                 Some(child) => {
                     node = &nodes[child as usize];
-                    if node.is_end_of_word {
-                        return true;
+                    if node.is_end_of_word() {
+                        return Some((i + 1, node.value));
                     }
                 }
-                None => return false,
+                None => return None,
             }
         }
-        false
+        None
+    }
+
+    fn exact_slot_packed(&self, word: &str) -> Option<u32> {
+        let mut node = self.nodes.get(ROOT as usize)?;
+        for &b in word.as_bytes() {
+            node = &self.nodes[Self::find_child(node, b)? as usize];
+        }
+        node.is_end_of_word().then_some(node.value)
     }
 
     /// Walk the trie and invoke `f` for every stored pattern.
     /// Used by consumers (e.g. dynamic block list compaction) that need to
     /// enumerate patterns without touching internal node layout.
     pub fn for_each_word<F: FnMut(&[u8])>(&self, mut f: F) {
+        self.for_each_entry(|word, _| f(word));
+    }
+
+    /// Walk the trie and invoke `f` for every stored pattern and its payload.
+    pub fn for_each_entry<F: FnMut(&[u8], &V)>(&self, mut f: F) {
+        let values = &self.values;
+        let mut emit = |word: &[u8], slot: u32| f(word, &values[slot as usize]);
         if let Some(build) = &self.build {
-            walk_build(build, &mut f);
+            walk_build(build, &mut emit);
         } else if !self.nodes.is_empty() {
-            walk_packed(&self.nodes, &mut f);
+            walk_packed(&self.nodes, &mut emit);
         }
     }
 
+    /// Number of nodes in the packed arena. Crate-internal: matchers that
+    /// layer extra links over the trie (see `substring`) index by node.
+    pub(crate) fn node_count(&self) -> usize {
+        debug_assert!(self.build.is_none(), "trie must be frozen");
+        self.nodes.len()
+    }
+
+    /// Child of packed node `idx` along edge `byte`.
+    #[inline(always)]
+    pub(crate) fn child(&self, idx: u32, byte: u8) -> Option<u32> {
+        Self::find_child(&self.nodes[idx as usize], byte)
+    }
+
+    /// Outgoing edges of packed node `idx` in ascending byte order.
+    pub(crate) fn edges(&self, idx: u32) -> impl Iterator<Item = (u8, u32)> + '_ {
+        let node = &self.nodes[idx as usize];
+        node.keys.iter().copied().zip(node.children.iter().copied())
+    }
+
+    /// `true` if a stored pattern ends at packed node `idx`.
+    #[inline(always)]
+    pub(crate) fn is_terminal(&self, idx: u32) -> bool {
+        self.nodes[idx as usize].is_end_of_word()
+    }
+
+    /// Payload of the pattern ending at packed node `idx`, which must be
+    /// terminal.
+    #[inline]
+    pub(crate) fn value(&self, idx: u32) -> &V {
+        &self.values[self.nodes[idx as usize].value as usize]
+    }
+
     /// Freeze into the packed representation. Callers generally don't need
     /// to call this — the first `contains_prefix` on a trie handed out by
     /// value will walk the build form directly, and any `&mut Trie` route
@@ -216,50 +424,74 @@ impl Trie {
     }
 }
 
+/// Build a [`PrefixMatch`] for the first `len` bytes of `text`. A stored
+/// pattern is a `&str` and shares its bytes with `text`, so `len` always
+/// lands on a char boundary.
+#[inline(always)]
+fn prefix_match(text: &str, len: usize) -> PrefixMatch<'_> {
+    PrefixMatch {
+        pattern: &text[..len],
+        len,
+    }
+}
+
+/// Seconds since the Unix epoch, for checking expiry cutoffs.
+pub(crate) fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Child lookup over the build-time form.
+#[inline]
+fn find_child_build(node: &BuildNode, b: u8) -> Option<u32> {
+    let keys = &node.keys[..];
+    if keys.len() <= LINEAR_SCAN_THRESHOLD {
+        for (i, &k) in keys.iter().enumerate() {
+            if k == b {
+                return Some(node.children[i]);
+            }
+            if k > b {
+                return None;
+            }
+        }
+        None
+    } else {
+        keys.binary_search(&b).ok().map(|pos| node.children[pos])
+    }
+}
+
 /// Linear scan over the build-time form. Same semantics as the packed lookup.
 #[inline]
-fn contains_prefix_build(build: &[BuildNode], text: &str) -> bool {
+fn prefix_slot_build(build: &[BuildNode], text: &str) -> Option<(usize, u32)> {
     if build.is_empty() {
-        return false;
+        return None;
     }
     let mut idx: u32 = ROOT;
-    for &b in text.as_bytes() {
-        let node = &build[idx as usize];
-        let keys = &node.keys[..];
-        let next = if keys.len() <= LINEAR_SCAN_THRESHOLD {
-            let mut found = None;
-            for (i, &k) in keys.iter().enumerate() {
-                if k == b {
-                    found = Some(node.children[i]);
-                    break;
-                }
-                if k > b {
-                    break;
-                }
-            }
-            found
-        } else {
-            keys.binary_search(&b).ok().map(|pos| node.children[pos])
-        };
-        match next {
-            Some(child) => {
-                idx = child;
-                if build[idx as usize].is_end_of_word {
-                    return true;
-                }
-            }
-            None => return false,
+    for (i, &b) in text.as_bytes().iter().enumerate() {
+        idx = find_child_build(&build[idx as usize], b)?;
+        let value = build[idx as usize].value;
+        if value != NO_VALUE {
+            return Some((i + 1, value));
         }
     }
-    false
+    None
 }
 
-fn walk_build(build: &[BuildNode], f: &mut dyn FnMut(&[u8])) {
+fn exact_slot_build(build: &[BuildNode], word: &str) -> Option<u32> {
+    let mut node = build.get(ROOT as usize)?;
+    for &b in word.as_bytes() {
+        node = &build[find_child_build(node, b)? as usize];
+    }
+    (node.value != NO_VALUE).then_some(node.value)
+}
+
+fn walk_build(build: &[BuildNode], f: &mut dyn FnMut(&[u8], u32)) {
     let mut stack: Vec<(u32, Vec<u8>)> = vec![(ROOT, Vec::new())];
     while let Some((idx, prefix)) = stack.pop() {
         let node = &build[idx as usize];
-        if node.is_end_of_word {
-            f(&prefix);
+        if node.value != NO_VALUE {
+            f(&prefix, node.value);
         }
         for (i, &k) in node.keys.iter().enumerate() {
             let mut next = prefix.clone();
@@ -269,12 +501,12 @@ fn walk_build(build: &[BuildNode], f: &mut dyn FnMut(&[u8])) {
     }
 }
 
-fn walk_packed(nodes: &[Node], f: &mut dyn FnMut(&[u8])) {
+fn walk_packed(nodes: &[Node], f: &mut dyn FnMut(&[u8], u32)) {
     let mut stack: Vec<(u32, Vec<u8>)> = vec![(ROOT, Vec::new())];
     while let Some((idx, prefix)) = stack.pop() {
         let node = &nodes[idx as usize];
-        if node.is_end_of_word {
-            f(&prefix);
+        if node.is_end_of_word() {
+            f(&prefix, node.value);
         }
         for (i, &k) in node.keys.iter().enumerate() {
             let mut next = prefix.clone();
@@ -284,10 +516,257 @@ fn walk_packed(nodes: &[Node], f: &mut dyn FnMut(&[u8])) {
     }
 }
 
+/// Prefix trie over borrowed, pre-packed arrays — the form `build.rs`
+/// emits for the built-in lists.
+///
+/// Node `i`'s edges are `keys[offsets[i]..offsets[i + 1]]`, sorted by byte,
+/// leading to the nodes at the same positions of `children`; `ends[i]`
+/// marks where a stored pattern ends. Node 0 is the root. Lookups follow
+/// the same shortest-prefix rules as [`Trie`], skipping expired patterns.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticTrie<'a> {
+    keys: &'a [u8],
+    children: &'a [u32],
+    offsets: &'a [u32],
+    ends: &'a [bool],
+    /// `(node, unix_secs)` for each pattern that stops matching at
+    /// `unix_secs`, sorted by node. Patterns not listed never expire.
+    expires: &'a [(u32, u64)],
+}
+
+impl<'a> StaticTrie<'a> {
+    /// Wrap packed arrays. Meant for generated code: malformed input can't
+    /// cause undefined behaviour, but lookups on it may panic.
+    pub const fn from_parts(
+        keys: &'a [u8],
+        children: &'a [u32],
+        offsets: &'a [u32],
+        ends: &'a [bool],
+    ) -> Self {
+        StaticTrie {
+            keys,
+            children,
+            offsets,
+            ends,
+            expires: &[],
+        }
+    }
+
+    /// Attach expiry cutoffs: `(node, unix_secs)` pairs sorted by node.
+    /// The pattern ending at `node` stops matching once the clock reaches
+    /// `unix_secs`. Only the clock is read, and only when such a pattern
+    /// is hit.
+    pub const fn with_expiry(mut self, expires: &'a [(u32, u64)]) -> Self {
+        self.expires = expires;
+        self
+    }
+
+    /// Check if any stored pattern is a prefix of `text`.
+    #[inline]
+    pub fn contains_prefix(&self, text: &str) -> bool {
+        self.prefix_len(text).is_some()
+    }
+
+    /// The shortest stored pattern that is a prefix of `text`.
+    #[inline]
+    pub fn find_prefix<'t>(&self, text: &'t str) -> Option<PrefixMatch<'t>> {
+        self.prefix_len(text).map(|len| prefix_match(text, len))
+    }
+
+    /// `true` if exactly `word` is stored and not expired.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.ends.is_empty() {
+            return false;
+        }
+        let mut node = ROOT;
+        for &b in word.as_bytes() {
+            match self.child(node, b) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.ends[node as usize] && self.is_live(node)
+    }
+
+    /// Number of stored patterns, expired or not.
+    pub fn len(&self) -> usize {
+        self.ends.iter().filter(|&&end| end).count()
+    }
+
+    /// `true` if no pattern is stored.
+    pub fn is_empty(&self) -> bool {
+        !self.ends.contains(&true)
+    }
+
+    /// Invoke `f` for every stored pattern, expired or not.
+    pub fn for_each_word<F: FnMut(&[u8])>(&self, mut f: F) {
+        self.for_each_entry(|word, _| f(word));
+    }
+
+    /// Invoke `f` for every stored pattern and the Unix time it expires
+    /// at, if any.
+    pub fn for_each_entry<F: FnMut(&[u8], Option<u64>)>(&self, mut f: F) {
+        if self.ends.is_empty() {
+            return;
+        }
+        let mut stack: Vec<(u32, Vec<u8>)> = vec![(ROOT, Vec::new())];
+        while let Some((idx, prefix)) = stack.pop() {
+            if self.ends[idx as usize] {
+                f(&prefix, self.expiry(idx));
+            }
+            for (byte, child) in self.edges(idx) {
+                let mut next = prefix.clone();
+                next.push(byte);
+                stack.push((child, next));
+            }
+        }
+    }
+
+    /// Byte length of the shortest stored pattern that prefixes `text`.
+    #[inline]
+    fn prefix_len(&self, text: &str) -> Option<usize> {
+        if self.ends.is_empty() {
+            return None;
+        }
+        let mut node = ROOT;
+        for (i, &b) in text.as_bytes().iter().enumerate() {
+            node = self.child(node, b)?;
+            if self.ends[node as usize] && self.is_live(node) {
+                return Some(i + 1);
+            }
+        }
+        None
+    }
+
+    /// Expiry cutoff of the pattern ending at `node`, if it has one.
+    fn expiry(&self, node: u32) -> Option<u64> {
+        self.expires
+            .binary_search_by_key(&node, |&(n, _)| n)
+            .ok()
+            .map(|i| self.expires[i].1)
+    }
+
+    /// `false` if the pattern ending at `node` has expired.
+    #[inline]
+    fn is_live(&self, node: u32) -> bool {
+        self.expires.is_empty() || self.expiry(node).is_none_or(|at| unix_now() < at)
+    }
+
+    #[inline(always)]
+    fn edge_range(&self, idx: u32) -> std::ops::Range<usize> {
+        self.offsets[idx as usize] as usize..self.offsets[idx as usize + 1] as usize
+    }
+
+    #[inline(always)]
+    fn child(&self, idx: u32, byte: u8) -> Option<u32> {
+        let range = self.edge_range(idx);
+        let start = range.start;
+        let keys = &self.keys[range];
+        let pos = if keys.len() <= LINEAR_SCAN_THRESHOLD {
+            let mut found = None;
+            for (i, &k) in keys.iter().enumerate() {
+                if k >= byte {
+                    found = (k == byte).then_some(i);
+                    break;
+                }
+            }
+            found?
+        } else {
+            keys.binary_search(&byte).ok()?
+        };
+        Some(self.children[start + pos])
+    }
+
+    fn edges(&self, idx: u32) -> impl Iterator<Item = (u8, u32)> + '_ {
+        let range = self.edge_range(idx);
+        self.keys[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.children[range].iter().copied())
+    }
+}
+
+impl PrefixMatcher for StaticTrie<'_> {
+    #[inline]
+    fn find_prefix<'a>(&self, text: &'a str) -> Option<PrefixMatch<'a>> {
+        StaticTrie::find_prefix(self, text)
+    }
+
+    #[inline]
+    fn contains_prefix(&self, text: &str) -> bool {
+        StaticTrie::contains_prefix(self, text)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// `["ab", "ac", "b"]`, packed in preorder.
+    static ABC: StaticTrie<'static> = StaticTrie::from_parts(
+        b"abbc",
+        &[1, 4, 2, 3],
+        &[0, 2, 4, 4, 4, 4],
+        &[false, false, true, true, true],
+    );
+
+    #[test]
+    fn static_trie_lookups() {
+        assert!(ABC.contains_prefix("abz"));
+        assert!(ABC.contains_prefix("ac"));
+        assert!(ABC.contains_prefix("b/"));
+        assert!(!ABC.contains_prefix("a"));
+        assert!(!ABC.contains_prefix("c"));
+        assert_eq!(ABC.find_prefix("acme").unwrap().pattern, "ac");
+        assert!(ABC.contains("ab"));
+        assert!(!ABC.contains("a"));
+        assert_eq!(ABC.len(), 3);
+
+        let mut words = Vec::new();
+        ABC.for_each_word(|w| words.push(String::from_utf8(w.to_vec()).unwrap()));
+        words.sort();
+        assert_eq!(words, ["ab", "ac", "b"]);
+
+        let empty = StaticTrie::from_parts(&[], &[], &[], &[]);
+        assert!(empty.is_empty());
+        assert!(!empty.contains_prefix("a"));
+        assert!(!empty.contains(""));
+    }
+
+    #[test]
+    fn static_trie_skips_expired_patterns() {
+        // "ab" (node 2) expired at the epoch, "b" (node 4) never does.
+        let trie = ABC.with_expiry(&[(2, 0), (4, u64::MAX)]);
+        assert!(!trie.contains_prefix("abz"));
+        assert!(!trie.contains("ab"));
+        assert!(trie.contains_prefix("acme"));
+        assert!(trie.contains_prefix("b/"));
+        assert_eq!(trie.len(), 3);
+
+        let mut entries = Vec::new();
+        trie.for_each_entry(|w, at| entries.push((String::from_utf8(w.to_vec()).unwrap(), at)));
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                ("ab".to_string(), Some(0)),
+                ("ac".to_string(), None),
+                ("b".to_string(), Some(u64::MAX)),
+            ]
+        );
+
+        // A longer live pattern still matches past an expired shorter one.
+        let nested = StaticTrie::from_parts(
+            b"abc",
+            &[1, 2, 3],
+            &[0, 1, 2, 3, 3],
+            &[false, true, false, true],
+        )
+        .with_expiry(&[(1, 0)]);
+        assert_eq!(nested.find_prefix("abcd").unwrap().pattern, "abc");
+        assert!(!nested.contains_prefix("ab"));
+    }
+
     #[test]
     fn insert_and_prefix_match() {
         let mut t = Trie::new();
@@ -330,6 +809,56 @@ mod tests {
         assert!(t.contains_prefix("xyz789"));
     }
 
+    #[test]
+    fn find_prefix_reports_matched_pattern() {
+        let mut t = Trie::new();
+        t.insert("https://static.hotjar.com/");
+        t.insert("https://static.hotjar.com/c/");
+        let m = t
+            .find_prefix("https://static.hotjar.com/c/hotjar-1.js")
+            .expect("should match");
+        assert_eq!(m.pattern, "https://static.hotjar.com/");
+        assert_eq!(m.len, "https://static.hotjar.com/".len());
+        assert_eq!(t.find_prefix("https://cdn.example.com/"), None);
+
+        t.shrink_to_fit();
+        let m = t.find_prefix("https://static.hotjar.com/x").unwrap();
+        assert_eq!(m.pattern, "https://static.hotjar.com/");
+        assert_eq!(t.find_prefix("https://static.hotjar"), None);
+    }
+
+    #[test]
+    fn payload_trie_reports_value() {
+        #[derive(Debug, PartialEq)]
+        enum Kind {
+            Script,
+            Xhr,
+        }
+        let mut t: Trie<Kind> = Trie::default();
+        t.insert_value("https://static.hotjar.com/", Kind::Script);
+        t.insert_value("https://sentry.io/api/", Kind::Xhr);
+        assert_eq!(t.len(), 2);
+
+        let (m, kind) = t.find_prefix_value("https://sentry.io/api/1/").unwrap();
+        assert_eq!(m.pattern, "https://sentry.io/api/");
+        assert_eq!(kind, &Kind::Xhr);
+        assert!(t.find_prefix_value("https://cdn.example.com/").is_none());
+
+        // Re-inserting replaces the payload without adding a pattern.
+        t.insert_value("https://sentry.io/api/", Kind::Script);
+        assert_eq!(t.len(), 2);
+        t.shrink_to_fit();
+        assert_eq!(t.get("https://sentry.io/api/"), Some(&Kind::Script));
+        assert_eq!(t.get("https://sentry.io/"), None);
+        let (_, kind) = t.find_prefix_value("https://static.hotjar.com/c").unwrap();
+        assert_eq!(kind, &Kind::Script);
+
+        let mut entries = Vec::new();
+        t.for_each_entry(|w, k| entries.push((w.to_vec(), k == &Kind::Script)));
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|(_, is_script)| *is_script));
+    }
+
     #[test]
     fn empty_trie_matches_nothing() {
         let t = Trie::new();
@@ -0,0 +1,85 @@
+//! Naming rules `build.rs` applies to `url_patterns/domains`, checked against
+//! the fixture trees in `tests/fixtures`.
+
+#[path = "../build/domains.rs"]
+mod domains;
+
+use std::path::{Path, PathBuf};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn names(dirs: &[domains::DomainDir]) -> Vec<(&str, &str, &str)> {
+    dirs.iter()
+        .map(|d| (d.name.as_str(), d.ident.as_str(), d.variant.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_multi_label_and_hyphenated_domains() {
+    let dirs = domains::scan(&fixture("domains")).unwrap();
+    assert_eq!(
+        names(&dirs),
+        [
+            ("1password.com", "domain_1password_com", "Domain1password"),
+            ("my-site.co.uk", "my_site_co_uk", "MySiteCo"),
+            ("news.bbc.co.uk", "news_bbc_co_uk", "NewsBbcCo"),
+            ("web.archive.org", "web_archive_org", "WebArchive"),
+            ("x.com", "x_com", "X"),
+            ("xn--80ak6aa92e.com", "xn_80ak6aa92e_com", "Xn80ak6aa92e"),
+        ]
+    );
+    let archive = &dirs[3];
+    assert_eq!(archive.module(), "web_archive_org_blockers");
+    assert_eq!(archive.trie("scripts"), "WEB_ARCHIVE_ORG_SCRIPTS_TRIE");
+    assert_eq!(archive.hosts("allow"), "WEB_ARCHIVE_ORG_ALLOW_HOSTS");
+    assert_eq!(archive.substrings("xhr"), "WEB_ARCHIVE_ORG_XHR_SUBSTRINGS");
+}
+
+#[test]
+fn test_repo_domains_keep_their_variants() {
+    let dirs = domains::scan(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/url_patterns/domains"
+    )))
+    .unwrap();
+    let find = |name: &str| dirs.iter().find(|d| d.name == name).unwrap();
+    assert_eq!(find("amazon.com").variant, "Amazon");
+    assert_eq!(find("web.archive.org").variant, "WebArchive");
+    assert_eq!(find("web.archive.org").ident, "web_archive_org");
+}
+
+#[test]
+fn test_clashing_variants_use_full_names() {
+    let dirs = domains::scan(&fixture("colliding_variant")).unwrap();
+    assert_eq!(
+        names(&dirs),
+        [("x.com", "x_com", "XCom"), ("x.org", "x_org", "XOrg")]
+    );
+}
+
+#[test]
+fn test_collisions_fail() {
+    let err = domains::scan(&fixture("colliding_ident")).unwrap_err();
+    assert!(err.contains("a-b.com") && err.contains("a.b.com"), "{err}");
+    assert!(err.contains("identifier `a_b_com`"), "{err}");
+
+    let err = domains::scan(&fixture("reserved")).unwrap_err();
+    assert!(err.contains("`Unknown` is reserved"), "{err}");
+}
+
+#[test]
+fn test_ident_rules() {
+    assert_eq!(
+        domains::ident("Web.Archive.org").unwrap(),
+        "web_archive_org"
+    );
+    assert_eq!(domains::ident("xn--p1ai").unwrap(), "xn_p1ai");
+    assert_eq!(domains::ident("a..b"), None);
+    assert_eq!(domains::ident("a_b.com"), None);
+    assert_eq!(domains::ident(""), None);
+    assert_eq!(domains::upper_camel("web_archive"), "WebArchive");
+}
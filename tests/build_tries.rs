@@ -0,0 +1,152 @@
+//! The arrays `build.rs` packs must answer exactly like a runtime `Trie`.
+
+#[path = "../build/static_trie.rs"]
+mod static_trie;
+
+use spider_network_blocker::host_trie::{HostTrie, StaticHostTrie};
+use spider_network_blocker::pattern_file::host_key;
+use spider_network_blocker::trie::{StaticTrie, Trie};
+use static_trie::PackedTrie;
+
+fn check(patterns: &[&str], queries: &[&str]) {
+    let packed = PackedTrie::new(patterns.iter().copied());
+    let fixed = StaticTrie::from_parts(
+        &packed.keys,
+        &packed.children,
+        &packed.offsets,
+        &packed.ends,
+    );
+    let mut trie = Trie::new();
+    for p in patterns.iter().filter(|p| !p.is_empty()) {
+        trie.insert(p);
+    }
+    trie.shrink_to_fit();
+
+    assert_eq!(fixed.len(), trie.len());
+    for q in queries.iter().chain(patterns) {
+        assert_eq!(fixed.find_prefix(q), trie.find_prefix(q), "{q:?}");
+        assert_eq!(fixed.contains(q), trie.get(q).is_some(), "{q:?}");
+    }
+
+    let mut fixed_words = Vec::new();
+    fixed.for_each_word(|w| fixed_words.push(w.to_vec()));
+    let mut trie_words = Vec::new();
+    trie.for_each_word(|w| trie_words.push(w.to_vec()));
+    fixed_words.sort();
+    trie_words.sort();
+    assert_eq!(fixed_words, trie_words);
+}
+
+#[test]
+fn test_packed_matches_runtime_trie() {
+    check(
+        &[
+            "https://ads.example.com/",
+            "https://ads.example.com/deeper/",
+            "https://tracker.example.com/pixel",
+            "/track",
+            "/track_visitor",
+            "analytics.",
+            "",
+            "https://ünïcode.example/",
+            "https://ads.example.com/",
+        ],
+        &[
+            "https://ads.example.com/banner.js",
+            "https://ads.example.com",
+            "https://tracker.example.com/pixel?id=1",
+            "https://tracker.example.com/",
+            "/track_visitor/1",
+            "/trac",
+            "analytics.min.js",
+            "https://ünïcode.example/x",
+            "",
+            "zzz",
+        ],
+    );
+}
+
+#[test]
+fn test_packed_wide_fan_out() {
+    // More than the linear-scan cutoff of edges under one node.
+    let patterns: Vec<String> = (b'a'..=b'z').map(|c| format!("/{}x", c as char)).collect();
+    let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+    check(&patterns, &["/ax1", "/mx", "/zx/", "/z", "/0x", "/"]);
+}
+
+#[test]
+fn test_packed_empty() {
+    check(&[], &["", "a"]);
+    check(&[""], &["", "a"]);
+}
+
+#[test]
+fn test_packed_host_keys_match_host_trie() {
+    let hosts = ["criteo.net", "hotjar.com", "static.criteo.net", "co"];
+    let keys: Vec<String> = hosts.iter().map(|h| host_key(h)).collect();
+    let packed = PackedTrie::new(keys.iter().map(String::as_str));
+    let fixed = StaticHostTrie::new(StaticTrie::from_parts(
+        &packed.keys,
+        &packed.children,
+        &packed.offsets,
+        &packed.ends,
+    ));
+    let mut trie = HostTrie::new();
+    for h in hosts {
+        trie.insert(h);
+    }
+
+    assert_eq!(fixed.len(), trie.len());
+    for host in [
+        "criteo.net",
+        "a.b.Static.Criteo.NET.",
+        "evilcriteo.net",
+        "script.hotjar.com",
+        "net",
+        "x.co",
+        "",
+    ] {
+        assert_eq!(fixed.find_host(host), trie.find_host(host), "{host:?}");
+    }
+    for url in [
+        "https://static.criteo.net/js/ld.js",
+        "//vars.hotjar.com/box.html",
+        "https://example.com/criteo.net/",
+    ] {
+        assert_eq!(fixed.contains_url(url), trie.contains_url(url), "{url:?}");
+    }
+}
+
+#[test]
+fn test_packed_source() {
+    assert_eq!(
+        PackedTrie::new(["ab", "ac", "b"]).to_rust(),
+        "crate::trie::StaticTrie::from_parts(&[97,98,98,99], &[1,4,2,3], &[0,2,4,4,4,4], \
+         &[false,false,true,true,true])"
+    );
+}
+
+#[test]
+fn test_packed_expiry() {
+    let packed = PackedTrie::with_expiry([
+        ("ab", Some(0)),
+        ("ac", None),
+        ("b", Some(5)),
+        ("b", Some(9)),
+        ("ac", Some(1)),
+    ]);
+    assert_eq!(packed.expires, [(2, 0), (4, 9)]);
+    assert!(packed.to_rust().ends_with(".with_expiry(&[(2,0),(4,9)])"));
+
+    let fixed = StaticTrie::from_parts(
+        &packed.keys,
+        &packed.children,
+        &packed.offsets,
+        &packed.ends,
+    )
+    .with_expiry(&packed.expires);
+    assert_eq!(fixed.len(), 3);
+    assert!(!fixed.contains_prefix("abz"));
+    assert!(fixed.contains_prefix("acme"));
+    assert!(!fixed.contains_prefix("b/"));
+}
@@ -1,8 +1,9 @@
-use spider_network_blocker::adblock::ADBLOCK_PATTERNS;
+use spider_network_blocker::adblock::{ADBLOCK_PATTERNS, ADBLOCK_PATTERNS_MATCHER};
 use spider_network_blocker::intercept_manager::NetworkInterceptManager;
 use spider_network_blocker::scripts::{
     URL_IGNORE_CSS, URL_IGNORE_EMBEDED_TRIE, URL_IGNORE_SCRIPT_BASE_PATHS,
-    URL_IGNORE_SCRIPT_STYLES_PATHS, URL_IGNORE_TRIE, URL_IGNORE_TRIE_PATHS,
+    URL_IGNORE_SCRIPT_BASE_PATHS_MATCHER, URL_IGNORE_SCRIPT_STYLES_PATHS, URL_IGNORE_TRIE,
+    URL_IGNORE_TRIE_PATHS, URL_IGNORE_TRIE_PATHS_MATCHER,
 };
 use spider_network_blocker::xhr::{URL_IGNORE_XHR_MEDIA_TRIE, URL_IGNORE_XHR_TRIE};
 
@@ -174,6 +175,16 @@ fn test_trie_paths_standalone() {
     assert!(!URL_IGNORE_TRIE_PATHS.contains_prefix("app.bundle.js"));
 }
 
+#[test]
+fn test_path_matchers_on_full_urls() {
+    assert!(URL_IGNORE_TRIE_PATHS_MATCHER.is_match("https://example.com/js/tracking.min.js?v=3"));
+    assert!(!URL_IGNORE_TRIE_PATHS_MATCHER.is_match("https://example.com/js/app.bundle.js"));
+    assert!(URL_IGNORE_SCRIPT_BASE_PATHS_MATCHER
+        .is_match("https://blog.example.com/wp-content/plugins/cookie-law-info/public/a.js"));
+    assert!(!URL_IGNORE_SCRIPT_BASE_PATHS_MATCHER
+        .is_match("https://blog.example.com/wp-content/plugins/woocommerce/assets/main.js"));
+}
+
 // ── CSS blocking ─────────────────────────────────────────────────────
 
 #[test]
@@ -220,11 +231,19 @@ fn test_adblock_patterns_no_empty_strings() {
     }
 }
 
+#[test]
+fn test_adblock_patterns_matcher_on_full_urls() {
+    assert!(ADBLOCK_PATTERNS_MATCHER.is_match("https://cdn.example.com/top-advertisement.png"));
+    assert!(ADBLOCK_PATTERNS_MATCHER.is_match("https://pagead2.googlesyndication.com/x"));
+    assert!(!ADBLOCK_PATTERNS_MATCHER.is_match("https://cdn.example.com/app.js"));
+}
+
 // ── Adblock engine tests (feature-gated) ─────────────────────────────
 
 #[cfg(feature = "adblock")]
 mod adblock_engine_tests {
     use spider_network_blocker::adblock::engine::{AdblockEngine, FilterListUrls};
+    use spider_network_blocker::resource_type::ResourceType;
 
     #[test]
     fn test_engine_creation_from_rules() {
@@ -390,6 +409,57 @@ mod adblock_engine_tests {
         assert!(!result.unwrap().matched);
     }
 
+    #[test]
+    fn test_engine_passes_request_type_strings_through() {
+        let engine = AdblockEngine::from_rules(["||plugins.example.com^$object"], false);
+
+        assert!(engine.should_block(
+            "https://plugins.example.com/player.swf",
+            "https://example.com",
+            "object"
+        ));
+        assert!(engine
+            .check_request(
+                "https://plugins.example.com/player.swf",
+                "https://example.com",
+                "object"
+            )
+            .is_some_and(|result| result.matched));
+        assert!(!engine.should_block(
+            "https://plugins.example.com/player.swf",
+            "https://example.com",
+            "script"
+        ));
+        assert!(!engine.should_block_resource(
+            "https://plugins.example.com/player.swf",
+            "https://example.com",
+            ResourceType::Other
+        ));
+    }
+
+    #[test]
+    fn test_engine_resource_type_entry_points() {
+        let engine = AdblockEngine::from_rules(["||ads.example.com^$xmlhttprequest"], false);
+
+        assert!(engine.should_block_resource(
+            "https://ads.example.com/track",
+            "https://example.com",
+            ResourceType::Fetch
+        ));
+        assert!(engine
+            .check_resource_request(
+                "https://ads.example.com/track",
+                "https://example.com",
+                ResourceType::Xhr
+            )
+            .is_some_and(|result| result.matched));
+        assert!(!engine.should_block_resource(
+            "https://ads.example.com/track",
+            "https://example.com",
+            ResourceType::Image
+        ));
+    }
+
     #[test]
     fn test_filter_list_urls_constants() {
         assert!(FilterListUrls::EASYLIST.starts_with("https://"));